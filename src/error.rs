@@ -0,0 +1,105 @@
+//! Error types returned by fallible [`IndexMap`] and [`IndexSet`] operations.
+//!
+//! [`IndexMap`]: crate::IndexMap
+//! [`IndexSet`]: crate::IndexSet
+
+use core::fmt;
+
+/// The error returned when a pivot key used for relative insertion is missing.
+///
+/// This `struct` is returned by the [`insert_before`] and [`insert_after`]
+/// methods on [`IndexMap`].
+///
+/// [`insert_before`]: crate::IndexMap::insert_before
+/// [`insert_after`]: crate::IndexMap::insert_after
+/// [`IndexMap`]: crate::IndexMap
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NoSuchPivot;
+
+impl fmt::Display for NoSuchPivot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the pivot key is not present in the map")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NoSuchPivot {}
+
+/// The error returned when an index is out of bounds.
+///
+/// This `struct` is returned by the [`get_index_checked`] method on [`IndexMap`].
+///
+/// [`get_index_checked`]: crate::IndexMap::get_index_checked
+/// [`IndexMap`]: crate::IndexMap
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IndexOutOfBounds {
+    /// The index that was out of bounds.
+    pub index: usize,
+    /// The length of the collection at the time of the access.
+    pub len: usize,
+}
+
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index out of bounds: the len is {} but the index is {}",
+            self.len, self.index
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IndexOutOfBounds {}
+
+/// The error returned when keys are not given in strictly ascending order.
+///
+/// This `struct` is returned by the [`try_from_sorted_pairs`] method on [`IndexMap`].
+///
+/// [`try_from_sorted_pairs`]: crate::IndexMap::try_from_sorted_pairs
+/// [`IndexMap`]: crate::IndexMap
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnsortedInput {
+    /// The position of the first key that is not greater than its predecessor.
+    pub index: usize,
+}
+
+impl fmt::Display for UnsortedInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the key at position {} is not greater than its predecessor",
+            self.index
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsortedInput {}
+
+/// The error returned when a collection cannot hold any more elements.
+///
+/// With the `compact-index` crate feature enabled an [`IndexMap`] or [`IndexSet`]
+/// can hold at most `u32::MAX + 1` elements.
+///
+/// This `struct` is returned by the [`IndexMap::try_insert`] and
+/// [`IndexSet::try_insert`] methods.
+///
+/// [`IndexMap`]: crate::IndexMap
+/// [`IndexSet`]: crate::IndexSet
+/// [`IndexMap::try_insert`]: crate::IndexMap::try_insert
+/// [`IndexSet::try_insert`]: crate::IndexSet::try_insert
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CapacityError;
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the collection has reached its maximum number of elements"
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
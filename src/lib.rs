@@ -31,6 +31,14 @@
 //!
 //! [`IndexMap`] and [`IndexSet`] are mostly drop-in compatible with the
 //! standard library's `HashMap` and `HashSet`.
+//!
+//! ### Crate Features
+//!
+//! - `std`: Enables implementations of `std::error::Error`. Enabled by default.
+//! - `serde`: Enables (de)serialization via the `serde` crate.
+//! - `compact-index`: Stores slot indices as `u32` instead of `usize` which
+//!   reduces the memory footprint of the key index on 64-bit platforms.
+//!   This limits [`IndexMap`] and [`IndexSet`] to at most `u32::MAX + 1` elements.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -40,6 +48,7 @@ extern crate std as alloc;
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+mod error;
 pub mod map;
 pub mod set;
 
@@ -49,16 +58,101 @@ mod serde;
 #[cfg(feature = "serde")]
 pub mod serde_seq;
 
+pub use self::error::{CapacityError, IndexOutOfBounds, NoSuchPivot, UnsortedInput};
 pub use self::map::IndexMap;
 pub use self::set::IndexSet;
 
+use core::ops::{Bound, Range, RangeBounds};
+
+#[cfg(test)]
+mod tests;
+
+/// The underlying representation of a [`SlotIndex`].
+#[cfg(not(feature = "compact-index"))]
+type RawSlotIndex = usize;
+
+/// The underlying representation of a [`SlotIndex`].
+#[cfg(feature = "compact-index")]
+type RawSlotIndex = u32;
+
 /// A slot index referencing a slot in an [`IndexMap`].
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct SlotIndex(usize);
+struct SlotIndex(RawSlotIndex);
 
 impl SlotIndex {
+    /// Creates a new [`SlotIndex`] from the raw `usize` index.
+    ///
+    /// # Panics
+    ///
+    /// If `index` cannot be represented by a [`SlotIndex`].
+    pub fn new(index: usize) -> Self {
+        match Self::try_new(index) {
+            Ok(slot) => slot,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Creates a new [`SlotIndex`] from the raw `usize` index.
+    ///
+    /// # Errors
+    ///
+    /// If `index` cannot be represented by a [`SlotIndex`].
+    #[cfg(not(feature = "compact-index"))]
+    pub fn try_new(index: usize) -> Result<Self, CapacityError> {
+        Ok(Self(index))
+    }
+
+    /// Creates a new [`SlotIndex`] from the raw `usize` index.
+    ///
+    /// # Errors
+    ///
+    /// If `index` cannot be represented by a [`SlotIndex`].
+    #[cfg(feature = "compact-index")]
+    pub fn try_new(index: usize) -> Result<Self, CapacityError> {
+        RawSlotIndex::try_from(index)
+            .map(Self)
+            .map_err(|_| CapacityError)
+    }
+
     /// Returns the raw `usize` index of the [`SlotIndex`].
+    #[cfg(not(feature = "compact-index"))]
     pub fn index(self) -> usize {
         self.0
     }
+
+    /// Returns the raw `usize` index of the [`SlotIndex`].
+    #[cfg(feature = "compact-index")]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Converts `range` into a [`Range`] of indices within bounds of `len`.
+///
+/// # Panics
+///
+/// If `range` is out of bounds of `len` or if its start is greater than its end.
+fn simplify_range<R>(range: R, len: usize) -> Range<usize>
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Unbounded => 0,
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start index overflowed"),
+    };
+    let end = match range.end_bound() {
+        Bound::Unbounded => len,
+        Bound::Excluded(&end) => end,
+        Bound::Included(&end) => end.checked_add(1).expect("range end index overflowed"),
+    };
+    assert!(
+        start <= end,
+        "range start index {start} is greater than range end index {end}"
+    );
+    assert!(
+        end <= len,
+        "range end index {end} out of range for length {len}"
+    );
+    start..end
 }
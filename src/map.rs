@@ -1,16 +1,25 @@
 //! An ordered map based on a B-Tree that keeps insertion order of elements.
 
-use super::SlotIndex;
+use super::{
+    simplify_range, CapacityError, IndexOutOfBounds, IndexSet, NoSuchPivot, SlotIndex,
+    UnsortedInput,
+};
 use alloc::collections::{btree_map, BTreeMap};
 use alloc::vec::IntoIter as VecIntoIter;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
+use core::cmp::{min, Ordering};
 use core::fmt;
 use core::iter::FusedIterator;
-use core::mem::replace;
-use core::ops::{Index, IndexMut};
+use core::mem::{replace, take};
+use core::ops::{Bound, Index, IndexMut, Range, RangeBounds};
+use core::slice::Chunks as SliceChunks;
 use core::slice::Iter as SliceIter;
 use core::slice::IterMut as SliceIterMut;
+use core::slice::Windows as SliceWindows;
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct Slot<K, V> {
@@ -41,6 +50,21 @@ impl<K, V> Slot<K, V> {
         (self.key, self.value)
     }
 
+    /// Converts the [`Slot`] into its `key`.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Converts the [`Slot`] into its `value`.
+    pub fn into_value(self) -> V {
+        self.value
+    }
+
+    /// Returns a shared reference to the key of the [`Slot`].
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
     /// Returns a shared reference to the value of the [`Slot`].
     pub fn value(&self) -> &V {
         &self.value
@@ -67,19 +91,66 @@ impl<K, V> Slot<K, V> {
 ///
 /// - Some operations such as `IndexMap::insert` now require `K: Clone`.
 /// - It is to be expected that this fork performs worse than the original
-/// [`indexmap` crate] implementation.
+///   [`indexmap` crate] implementation.
 /// - The implementation is based on `BTreeMap` internally instead of
-/// `HashMap` which has the effect that methods no longer require `K: Hash`
-/// but `K: Ord` instead.
+///   `HashMap` which has the effect that methods no longer require `K: Hash`
+///   but `K: Ord` instead.
 ///
 /// [`indexmap` crate]: https://crates.io/crates/indexmap
 /// [`wasmparser-nostd` crate]: https://crates.io/crates/wasmparser-nostd
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone)]
 pub struct IndexMap<K, V> {
     /// A mapping from keys to slot indices.
     key2slot: BTreeMap<K, SlotIndex>,
     /// A vector holding all slots of key value pairs.
     slots: Vec<Slot<K, V>>,
+    /// Whether new key-value pairs are expected to arrive in ascending key order.
+    ///
+    /// This is merely a hint to speed up extending the map and does not
+    /// affect the contents of the map.
+    sorted_hint: bool,
+}
+
+impl<K, V> PartialEq for IndexMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key2slot == other.key2slot && self.slots == other.slots
+    }
+}
+
+impl<K, V> Eq for IndexMap<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> PartialOrd for IndexMap<K, V>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.key2slot.partial_cmp(&other.key2slot) {
+            Some(Ordering::Equal) => self.slots.partial_cmp(&other.slots),
+            ordering => ordering,
+        }
+    }
+}
+
+impl<K, V> Ord for IndexMap<K, V>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key2slot
+            .cmp(&other.key2slot)
+            .then_with(|| self.slots.cmp(&other.slots))
+    }
 }
 
 impl<K, V> Default for IndexMap<K, V> {
@@ -96,6 +167,7 @@ impl<K, V> IndexMap<K, V> {
         Self {
             key2slot: BTreeMap::new(),
             slots: Vec::new(),
+            sorted_hint: false,
         }
     }
 
@@ -106,14 +178,101 @@ impl<K, V> IndexMap<K, V> {
         Self {
             key2slot: BTreeMap::new(),
             slots: Vec::with_capacity(capacity),
+            sorted_hint: false,
+        }
+    }
+
+    /// Constructs a new, empty [`IndexMap`] with at least the specified capacity
+    /// that expects to be extended with keys in ascending order.
+    ///
+    /// Extending the map with key-value pairs in strictly ascending key order,
+    /// all greater than the keys already in the map, bulk-builds the key index
+    /// instead of inserting every key one by one if the run of ascending keys is
+    /// at least as long as the map. Once a key arrives out of order the remaining
+    /// key-value pairs are inserted as usual.
+    ///
+    /// The hint never affects the contents of the map, only the performance.
+    ///
+    /// Does not allocate if `capacity` is zero.
+    pub fn with_capacity_and_sorted_hint(capacity: usize) -> Self {
+        Self {
+            sorted_hint: true,
+            ..Self::with_capacity(capacity)
+        }
+    }
+
+    /// Creates a new map from key-value pairs given in strictly ascending key order.
+    ///
+    /// The keys are bulk-inserted into the key index at once.
+    ///
+    /// # Errors
+    ///
+    /// If a key is not greater than the key preceding it, which includes
+    /// duplicate keys. The error carries the position of the offending key.
+    pub fn try_from_sorted_pairs<I>(iter: I) -> Result<Self, UnsortedInput>
+    where
+        K: Ord + Clone,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut iter = iter.into_iter();
+        let mut map = Self::with_capacity(iter.size_hint().0);
+        match map.extend_sorted(&mut iter) {
+            Some(_) => Err(UnsortedInput { index: map.len() }),
+            None => Ok(map),
         }
     }
 
+    /// Returns the number of key-value pairs the map can hold without reallocating.
+    ///
+    /// # Note
+    ///
+    /// This only refers to the storage of the key-value pairs.
+    /// The b-tree based key index has no notion of capacity.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
     /// Reserve capacity for at least `additional` more key-value pairs.
     pub fn reserve(&mut self, additional: usize) {
         self.slots.reserve(additional);
     }
 
+    /// Reserve capacity for all keys in `keys` that are not yet present in the map.
+    pub fn reserve_for_keys(&mut self, keys: &IndexSet<K>)
+    where
+        K: Ord,
+    {
+        let additional = keys.iter().filter(|key| !self.contains_key(*key)).count();
+        self.reserve(additional);
+    }
+
+    /// Shrinks the capacity of the map as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.slots.shrink_to_fit();
+    }
+
+    /// Compacts the map if its capacity is disproportionately large compared to its length.
+    ///
+    /// The map is considered sparse if its capacity exceeds its length by more
+    /// than a factor of four, which is typically the case after removing most of
+    /// its key-value pairs. Only then its capacity is shrunk as much as possible
+    /// and its key index is rebuilt from scratch in order to release memory.
+    /// Otherwise this is a no-op.
+    ///
+    /// Note that this also releases capacity that has been reserved on purpose.
+    pub fn shrink_index_if_sparse(&mut self)
+    where
+        K: Ord,
+    {
+        /// The factor by which capacity must exceed length for a map to be sparse.
+        const SPARSE_RATIO: usize = 4;
+        if self.slots.capacity() / SPARSE_RATIO <= self.len() {
+            return;
+        }
+        self.shrink_to_fit();
+        self.key2slot = take(&mut self.key2slot).into_iter().collect();
+    }
+
     /// Returns the number of elements in the map.
     pub fn len(&self) -> usize {
         self.slots.len()
@@ -121,21 +280,152 @@ impl<K, V> IndexMap<K, V> {
 
     /// Returns `true` if the map contains no elements.
     pub fn is_empty(&self) -> bool {
-        self.len() != 0
+        self.len() == 0
+    }
+
+    /// Returns statistics about the internal state of the map.
+    ///
+    /// This is intended for monitoring long-lived maps, e.g. for excess capacity.
+    /// Use [`IndexMap::check_consistency`] to additionally verify the key index.
+    pub fn stats(&self) -> MapStats {
+        MapStats {
+            len: self.len(),
+            slots_capacity: self.slots.capacity(),
+            key2slot_len: self.key2slot.len(),
+        }
+    }
+
+    /// Returns `true` if the key index and the key-value pairs of the map agree with each other.
+    ///
+    /// This is always `true` unless the map has been corrupted, e.g. by a key
+    /// type whose [`Ord`] implementation changed while it was stored in the map.
+    ///
+    /// # Note
+    ///
+    /// This visits all keys of the map and thus takes O(n) time.
+    pub fn check_consistency(&self) -> bool
+    where
+        K: Ord,
+    {
+        self.key2slot.len() == self.slots.len()
+            && self.key2slot.iter().all(|(key, slot)| {
+                self.slots
+                    .get(slot.index())
+                    .is_some_and(|slot| slot.key == *key)
+            })
+    }
+
+    /// Recomputes the slot indices of all keys from the positions of their key-value pairs.
+    ///
+    /// The slot indices of a map are always kept in sync with the positions of its
+    /// key-value pairs, so this never changes a well-formed map.
+    pub fn normalize(&mut self)
+    where
+        K: Ord,
+    {
+        self.reindex(0..self.len());
     }
 
     /// Returns true if the map contains a value for the specified key.
     ///
     /// The key may be any borrowed form of the map’s key type,
     /// but the ordering on the borrowed form must match the ordering on the key type.
-    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
     where
         K: Borrow<Q> + Ord,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
         self.key2slot.contains_key(key)
     }
 
+    /// Returns the number of keys in the map that are less than `bound`.
+    ///
+    /// # Note
+    ///
+    /// This visits all keys less than `bound` and thus takes O(n) time in the
+    /// worst case.
+    pub fn count_keys_below<Q>(&self, bound: &Q) -> usize
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.key2slot
+            .range::<Q, _>((Bound::Unbounded, Bound::Excluded(bound)))
+            .count()
+    }
+
+    /// Returns `true` if `self` has no keys in common with `other`.
+    pub fn is_disjoint_keys<W>(&self, other: &IndexMap<K, W>) -> bool
+    where
+        K: Ord,
+    {
+        if self.len() <= other.len() {
+            self.key2slot.keys().all(|key| !other.contains_key(key))
+        } else {
+            other.key2slot.keys().all(|key| !self.contains_key(key))
+        }
+    }
+
+    /// Compares `self` against `other` and returns their differences.
+    ///
+    /// The returned [`MapDiff`] treats `self` as the old state and `other`
+    /// as the new state of the map.
+    pub fn diff<'a>(&'a self, other: &'a IndexMap<K, V>) -> MapDiff<'a, K, V>
+    where
+        K: Ord,
+        V: PartialEq,
+    {
+        MapDiff {
+            old: self,
+            new: other,
+        }
+    }
+
+    /// Applies `patch` to the map.
+    ///
+    /// Applying the [`MapPatch`] of `old.diff(&new)` to a map equal to `old`
+    /// results in a map that contains the same key-value pairs as `new`.
+    ///
+    /// Removed keys are removed preserving the insertion order of the remaining
+    /// key-value pairs and changed values are updated in place. Added key-value
+    /// pairs are appended to the end of the map in the insertion order of the new
+    /// map. Therefore the insertion order of the resulting map might differ from
+    /// the insertion order of the new map.
+    pub fn apply_patch(&mut self, patch: &MapPatch<K, V>)
+    where
+        K: Ord + Clone,
+        V: Clone,
+    {
+        self.remove_all_keys(patch.removals.iter().cloned());
+        for (key, value) in &patch.changes {
+            match self.get_full_mut(key) {
+                Some((_index, _key, old)) => old.clone_from(value),
+                None => {
+                    self.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        self.reserve(patch.additions.len());
+        for (key, value) in &patch.additions {
+            self.insert(key.clone(), value.clone());
+        }
+    }
+
+    /// Gets an iterator over the keys that are present in both `self` and `other`
+    /// alongside their values in both maps.
+    ///
+    /// The keys are yielded in the insertion order of `self`.
+    pub fn common_keys<'a, W>(
+        &'a self,
+        other: &'a IndexMap<K, W>,
+    ) -> impl Iterator<Item = (&'a K, &'a V, &'a W)> + 'a
+    where
+        K: Ord,
+    {
+        self.iter()
+            .filter_map(move |(key, value)| other.get(key).map(|other| (key, value, other)))
+    }
+
     /// Inserts a key-value pair into the map.
     ///
     /// If the map did not have this key present, `None` is returned.
@@ -151,6 +441,26 @@ impl<K, V> IndexMap<K, V> {
             .map(|(_index, old_value)| old_value)
     }
 
+    /// Inserts a key-value pair into the map.
+    ///
+    /// Behaves like [`IndexMap::insert`] but returns an error instead of panicking
+    /// if the map cannot hold any more key-value pairs.
+    ///
+    /// # Errors
+    ///
+    /// If the key is not present and the map already holds the maximum number
+    /// of key-value pairs. This can only happen with the `compact-index` crate
+    /// feature enabled.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, CapacityError>
+    where
+        K: Ord + Clone,
+    {
+        if !self.contains_key(&key) {
+            SlotIndex::try_new(self.len())?;
+        }
+        Ok(self.insert(key, value))
+    }
+
     /// Inserts a key-value pair into the map.
     ///
     /// Returns the unique index to the key-value pair alongside the previous value.
@@ -167,265 +477,1942 @@ impl<K, V> IndexMap<K, V> {
         match self.key2slot.entry(key.clone()) {
             btree_map::Entry::Vacant(entry) => {
                 let new_slot = self.slots.len();
-                entry.insert(SlotIndex(new_slot));
+                entry.insert(SlotIndex::new(new_slot));
                 self.slots.push(Slot::new(key, value));
                 None
             }
             btree_map::Entry::Occupied(entry) => {
                 let index = entry.get().index();
-                let new_slot = Slot::new(key, value);
-                let old_slot = replace(&mut self.slots[index], new_slot);
-                Some((index, old_slot.value))
+                Some((index, replace(&mut self.slots[index].value, value)))
             }
         }
     }
 
-    /// Gets the given key’s corresponding entry in the map for in-place manipulation.
-    pub fn entry(&mut self, key: K) -> Entry<K, V>
+    /// Inserts a key-value pair into the map, replacing the key of an existing pair.
+    ///
+    /// Returns the unique index to the key-value pair alongside the replaced pair.
+    ///
+    /// If the map did not have this key present, `None` is returned.
+    ///
+    /// If the map did have this key present, both key and value are updated,
+    /// and the old key-value pair is returned. The key-value pair keeps its
+    /// position; this matters for types that can be `==` without being identical.
+    pub fn replace_full(&mut self, key: K, value: V) -> (usize, Option<(K, V)>)
     where
         K: Ord + Clone,
     {
-        match self.key2slot.entry(key) {
-            btree_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry {
-                vacant: entry,
-                slots: &mut self.slots,
-            }),
-            btree_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry {
-                occupied: entry,
-                slots: &mut self.slots,
-            }),
+        match self.key2slot.remove_entry(&key) {
+            Some((_old_key, slot)) => {
+                let index = slot.index();
+                self.key2slot.insert(key.clone(), slot);
+                let old_slot = replace(&mut self.slots[index], Slot::new(key, value));
+                (index, Some(old_slot.into_pair()))
+            }
+            None => {
+                let index = self.slots.len();
+                self.key2slot.insert(key.clone(), SlotIndex::new(index));
+                self.slots.push(Slot::new(key, value));
+                (index, None)
+            }
         }
     }
 
-    /// Returns a reference to the value corresponding to the key.
+    /// Replaces the value of the key with `value` if the key is present in the map.
     ///
-    /// The key may be any borrowed form of the map’s key type,
-    /// but the ordering on the borrowed form must match the ordering on the key type.
-    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    /// Returns the old value if the key was present in the map.
+    ///
+    /// Unlike [`IndexMap::insert`] this never inserts a new key-value pair.
+    /// If the key is not present in the map `value` is dropped and `None` is returned.
+    pub fn swap_value<Q>(&mut self, key: &Q, value: V) -> Option<V>
     where
         K: Borrow<Q> + Ord,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
-        self.key2slot
-            .get(key)
-            .map(|slot| &self.slots[slot.index()].value)
+        let index = self.get_index_of(key)?;
+        Some(replace(self.slots[index].value_mut(), value))
     }
 
-    /// Returns the key-value pair corresponding to the supplied key.
+    /// Swaps the positions of the key-value pairs at indices `a` and `b`.
     ///
-    /// The supplied key may be any borrowed form of the map's key type,
-    /// but the ordering on the borrowed form *must* match the ordering
-    /// on the key type.
-    pub fn get_key_value<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    /// # Panics
+    ///
+    /// If `a` or `b` are out of bounds.
+    pub fn swap_indices(&mut self, a: usize, b: usize)
     where
-        K: Borrow<Q> + Ord,
-        Q: Ord,
+        K: Ord,
     {
-        self.key2slot
-            .get_key_value(key)
-            .map(|(key, slot)| (key, &self.slots[slot.index()].value))
+        self.slots.swap(a, b);
+        self.reindex(a..a + 1);
+        self.reindex(b..b + 1);
     }
 
-    /// Returns the key-value pair corresponding to the supplied key
-    /// as well as the unique index of the returned key-value pair.
+    /// Swaps the positions of the key-value pairs of the keys `a` and `b`.
     ///
-    /// The supplied key may be any borrowed form of the map's key type,
-    /// but the ordering on the borrowed form *must* match the ordering
-    /// on the key type.
-    pub fn get_full<Q: ?Sized>(&self, key: &Q) -> Option<(usize, &K, &V)>
+    /// Returns `false` and leaves the map unchanged if either key is not
+    /// present in the map.
+    pub fn swap_positions_of<Q>(&mut self, a: &Q, b: &Q) -> bool
     where
         K: Borrow<Q> + Ord,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
-        self.key2slot.get_key_value(key).map(|(key, slot)| {
-            let index = slot.index();
-            let value = &self.slots[index].value;
-            (index, key, value)
-        })
+        match (self.get_index_of(a), self.get_index_of(b)) {
+            (Some(a), Some(b)) => {
+                self.swap_indices(a, b);
+                true
+            }
+            _ => false,
+        }
     }
 
-    /// Returns the unique index corresponding to the supplied key.
+    /// Inserts a key-value pair into the map right before the `pivot` key.
     ///
-    /// The supplied key may be any borrowed form of the map's key type,
-    /// but the ordering on the borrowed form *must* match the ordering
-    /// on the key type.
-    pub fn get_index_of<Q: ?Sized>(&self, key: &Q) -> Option<usize>
+    /// All key-value pairs starting at the position of `pivot` are shifted
+    /// one position towards the end in order to make room for the new pair.
+    ///
+    /// If the map did not have this key present, `Ok(None)` is returned.
+    ///
+    /// If the map did have this key present, the value is updated in place,
+    /// and the old value is returned. The key-value pair is not moved.
+    ///
+    /// # Errors
+    ///
+    /// If the `pivot` key is not present in the map. The map is left unchanged.
+    pub fn insert_before<Q>(
+        &mut self,
+        pivot: &Q,
+        key: K,
+        value: V,
+    ) -> Result<Option<V>, NoSuchPivot>
     where
-        K: Borrow<Q> + Ord,
-        Q: Ord,
+        K: Borrow<Q> + Ord + Clone,
+        Q: ?Sized + Ord,
     {
-        self.key2slot.get(key).copied().map(SlotIndex::index)
-    }
-
-    /// Returns a shared reference to the key-value pair at the given index.
-    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
-        self.slots.get(index).map(Slot::as_pair)
+        let index = self.get_index_of(pivot).ok_or(NoSuchPivot)?;
+        Ok(self.insert_at(index, key, value))
     }
 
-    /// Returns an exclusive reference to the key-value pair at the given index.
-    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
-        self.slots.get_mut(index).map(Slot::as_pair_mut)
+    /// Inserts a key-value pair into the map right after the `pivot` key.
+    ///
+    /// All key-value pairs following the position of `pivot` are shifted
+    /// one position towards the end in order to make room for the new pair.
+    ///
+    /// If the map did not have this key present, `Ok(None)` is returned.
+    ///
+    /// If the map did have this key present, the value is updated in place,
+    /// and the old value is returned. The key-value pair is not moved.
+    ///
+    /// # Errors
+    ///
+    /// If the `pivot` key is not present in the map. The map is left unchanged.
+    pub fn insert_after<Q>(&mut self, pivot: &Q, key: K, value: V) -> Result<Option<V>, NoSuchPivot>
+    where
+        K: Borrow<Q> + Ord + Clone,
+        Q: ?Sized + Ord,
+    {
+        let index = self.get_index_of(pivot).ok_or(NoSuchPivot)?;
+        Ok(self.insert_at(index + 1, key, value))
     }
 
-    /// Gets an iterator over the entries of the map, sorted by key.
-    pub fn iter(&self) -> Iter<K, V> {
-        Iter {
-            iter: self.slots.iter(),
+    /// Inserts all key-value pairs of `pairs` into the map in order.
+    ///
+    /// Reserves capacity for all key-value pairs up front.
+    ///
+    /// If a key is already present, either in the map or earlier in `pairs`,
+    /// its value is updated with the last value given for it in `pairs`
+    /// and it keeps its position.
+    pub fn extend_from_slice(&mut self, pairs: &[(K, V)])
+    where
+        K: Ord + Copy,
+        V: Copy,
+    {
+        self.reserve(pairs.len());
+        for &(key, value) in pairs {
+            self.insert(key, value);
         }
     }
 
-    /// Gets a mutable iterator over the entries of the map, sorted by key.
-    pub fn iter_mut(&mut self) -> IterMut<K, V> {
-        IterMut {
-            iter: self.slots.iter_mut(),
-        }
+    /// Adds all key-value pairs of `iter` to the map and returns the map for chaining.
+    ///
+    /// Reserves capacity for the lower bound of the size hint of `iter` up front.
+    ///
+    /// If a key is already present, either in the map or earlier in `iter`,
+    /// its value is updated with the last value given for it in `iter`
+    /// and it keeps its position.
+    pub fn collect_into<I>(&mut self, iter: I) -> &mut Self
+    where
+        K: Ord + Clone,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        self.extend(iter);
+        self
     }
 
-    /// Gets an iterator over the values of the map, in order by key.
-    pub fn values(&self) -> Values<K, V> {
-        Values {
-            iter: self.slots.iter(),
+    /// Merges the key-value pairs of `iter` into the map.
+    ///
+    /// If both the map and `iter` are in strictly ascending key order the key-value
+    /// pairs are merged in a single linear pass, so that the map stays in ascending
+    /// key order, and the key index is rebuilt only once. The values of keys that
+    /// are already present in the map are updated with the values of `iter`.
+    ///
+    /// Otherwise the key-value pairs of `iter` are inserted one after another
+    /// as if by [`IndexMap::insert`].
+    pub fn bulk_insert_sorted<I>(&mut self, iter: I)
+    where
+        K: Ord + Clone,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let pairs: Vec<(K, V)> = iter.into_iter().collect();
+        if pairs.is_empty() {
+            return;
+        }
+        let is_sorted = self.slots.windows(2).all(|w| w[0].key < w[1].key)
+            && pairs.windows(2).all(|w| w[0].0 < w[1].0);
+        if !is_sorted {
+            self.extend(pairs);
+            return;
         }
+        let mut old = take(&mut self.slots).into_iter().peekable();
+        let mut new = pairs.into_iter().peekable();
+        let mut merged = Vec::with_capacity(old.len() + new.len());
+        loop {
+            let ordering = match (old.peek(), new.peek()) {
+                (Some(old), Some((key, _))) => old.key.cmp(key),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+            let slot = match ordering {
+                Ordering::Less => old.next().expect("checked by peek"),
+                Ordering::Greater => {
+                    let (key, value) = new.next().expect("checked by peek");
+                    Slot::new(key, value)
+                }
+                Ordering::Equal => {
+                    let slot = old.next().expect("checked by peek");
+                    let (_key, value) = new.next().expect("checked by peek");
+                    Slot::new(slot.key, value)
+                }
+            };
+            merged.push(slot);
+        }
+        self.key2slot = merged
+            .iter()
+            .enumerate()
+            .map(|(index, slot)| (slot.key.clone(), SlotIndex::new(index)))
+            .collect();
+        self.slots = merged;
     }
 
-    /// Gets a mutable iterator over the values of the map, in order by key.
-    pub fn values_mut(&mut self) -> ValuesMut<K, V> {
-        ValuesMut {
-            iter: self.slots.iter_mut(),
+    /// Moves all key-value pairs from `other` whose keys are not present in `self`
+    /// to the end of `self` in the insertion order of `other`.
+    ///
+    /// Key-value pairs whose keys are already present in `self` are left in `other`
+    /// preserving their insertion order and the values in `self` are not updated.
+    pub fn append_new(&mut self, other: &mut IndexMap<K, V>)
+    where
+        K: Ord,
+    {
+        for slot in take(&mut other.slots) {
+            if self.contains_key(&slot.key) {
+                other.slots.push(slot);
+                continue;
+            }
+            let (key, _slot) = other
+                .key2slot
+                .remove_entry(&slot.key)
+                .expect("IndexMap: missing slot index for key");
+            self.key2slot.insert(key, SlotIndex::new(self.slots.len()));
+            self.slots.push(slot);
         }
+        other.reindex(0..other.len());
     }
 
-    /// Clears the map, removing all elements.
-    pub fn clear(&mut self) {
-        self.key2slot.clear();
-        self.slots.clear();
+    /// Removes the key from the map, returning its value if the key was previously in the map.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    ///
+    /// Returns `None` if the key is not present in the map.
+    pub fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.swap_remove_full(key)
+            .map(|(_index, _key, value)| value)
     }
-}
-
-impl<'a, K, Q, V> Index<&'a Q> for IndexMap<K, V>
-where
-    K: Borrow<Q> + Ord,
-    Q: Ord,
-{
-    type Output = V;
 
-    fn index(&self, key: &'a Q) -> &Self::Output {
-        self.get(key).expect("no entry found for key")
+    /// Removes the key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    ///
+    /// Returns `None` if the key is not present in the map.
+    pub fn swap_remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.swap_remove_full(key)
+            .map(|(_index, key, value)| (key, value))
     }
-}
-
-impl<K, V> Index<usize> for IndexMap<K, V> {
-    type Output = V;
 
-    fn index(&self, index: usize) -> &Self::Output {
-        let (_key, value) = self
-            .get_index(index)
-            .expect("IndexMap: index out of bounds");
-        value
+    /// Removes the key from the map, returning its former index as well as the
+    /// stored key and value if the key was previously in the map.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    ///
+    /// Returns `None` if the key is not present in the map.
+    pub fn swap_remove_full<Q>(&mut self, key: &Q) -> Option<(usize, K, V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        let index = self.key2slot.remove(key)?.index();
+        let (key, value) = self.swap_remove_slot(index).into_pair();
+        Some((index, key, value))
     }
-}
 
-impl<K, V> IndexMut<usize> for IndexMap<K, V> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        let (_key, value) = self
+    /// Removes the key from the map, returning its value as well as the key that
+    /// has been moved into its position alongside the index of that position.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    ///
+    /// The moved key is `None` if the removed key-value pair was the last one in the
+    /// map. This allows callers to keep external indices into the map up to date.
+    ///
+    /// Returns `None` if the key is not present in the map.
+    pub fn swap_remove_reporting<Q>(&mut self, key: &Q) -> Option<(V, Option<(usize, &K)>)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        let (index, _key, value) = self.swap_remove_full(key)?;
+        let moved = self.slots.get(index).map(|slot| (index, &slot.key));
+        Some((value, moved))
+    }
+
+    /// Removes the key-value pair at the given index, returning the key and value.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    pub fn swap_remove_entry_index(&mut self, index: usize) -> Option<(K, V)>
+    where
+        K: Ord,
+    {
+        let key = &self.slots.get(index)?.key;
+        self.key2slot.remove(key);
+        Some(self.swap_remove_slot(index).into_pair())
+    }
+
+    /// Removes the first key-value pair in insertion order whose value satisfies `f`,
+    /// returning its key and value.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    ///
+    /// Returns `None` if no value satisfies `f`.
+    pub fn swap_remove_by_value<F>(&mut self, f: F) -> Option<(K, V)>
+    where
+        K: Ord,
+        F: FnMut(&V) -> bool,
+    {
+        let index = self.index_of_value(f)?;
+        self.swap_remove_entry_index(index)
+    }
+
+    /// Removes the key-value pairs within the given range of indices and returns them.
+    ///
+    /// The removed key-value pairs are returned in their original order.
+    ///
+    /// Like [`Vec::swap_remove`], the range is filled by moving key-value pairs
+    /// from the end of the map into it instead of shifting all of the key-value
+    /// pairs that follow it. **This perturbs the position of those key-value pairs!**
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds or if its start is greater than its end.
+    pub fn swap_remove_range<R>(&mut self, range: R) -> Vec<(K, V)>
+    where
+        K: Ord,
+        R: RangeBounds<usize>,
+    {
+        let len = self.len();
+        let Range { start, end } = simplify_range(range, len);
+        let removed_len = end - start;
+        let moved_len = min(removed_len, len - end);
+        for offset in 0..moved_len {
+            self.slots.swap(start + offset, len - moved_len + offset);
+        }
+        let mut removed: Vec<(K, V)> = self
+            .slots
+            .drain(len - removed_len..)
+            .map(|slot| {
+                self.key2slot.remove(&slot.key);
+                slot.into_pair()
+            })
+            .collect();
+        // Pairs that have not been swapped are located in front of the swapped ones.
+        removed.rotate_left(removed_len - moved_len);
+        self.reindex(start..start + moved_len);
+        removed
+    }
+
+    /// Removes the key from the map, returning its value if the key was previously in the map.
+    ///
+    /// Like [`Vec::remove`], the key-value pair is removed by shifting all of the
+    /// key-value pairs that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those key-value pairs!**
+    ///
+    /// Returns `None` if the key is not present in the map.
+    pub fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.shift_remove_full(key)
+            .map(|(_index, _key, value)| value)
+    }
+
+    /// Removes the key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// Like [`Vec::remove`], the key-value pair is removed by shifting all of the
+    /// key-value pairs that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those key-value pairs!**
+    ///
+    /// Returns `None` if the key is not present in the map.
+    pub fn shift_remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.shift_remove_full(key)
+            .map(|(_index, key, value)| (key, value))
+    }
+
+    /// Removes the key from the map, returning its former index as well as the
+    /// stored key and value if the key was previously in the map.
+    ///
+    /// Like [`Vec::remove`], the key-value pair is removed by shifting all of the
+    /// key-value pairs that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those key-value pairs!**
+    ///
+    /// Returns `None` if the key is not present in the map.
+    pub fn shift_remove_full<Q>(&mut self, key: &Q) -> Option<(usize, K, V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        let index = self.key2slot.remove(key)?.index();
+        let (key, value) = self.shift_remove_slot(index).into_pair();
+        Some((index, key, value))
+    }
+
+    /// Removes the last key-value pair in insertion order and returns it.
+    ///
+    /// Like [`Vec::pop`], this does not perturb the index of any other key-value pair.
+    ///
+    /// Returns `None` if the map is empty.
+    pub fn pop(&mut self) -> Option<(K, V)>
+    where
+        K: Ord,
+    {
+        let slot = self.slots.pop()?;
+        self.key2slot.remove(&slot.key);
+        Some(slot.into_pair())
+    }
+
+    /// Gets the given key’s corresponding entry in the map for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V>
+    where
+        K: Ord + Clone,
+    {
+        // Occupied entries borrow the whole map since removing them reindexes
+        // other keys. The borrow checker does not allow to borrow the whole map
+        // in one branch of a `btree_map::Entry` and to return its vacant entry
+        // in the other branch. Therefore vacant entries look up their key twice:
+        // once here and once more upon insertion.
+        match self.get_index_of(&key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+
+    /// Gets the entry of every key in `keys` in turn and calls `f` with the
+    /// key and its entry for in-place manipulation.
+    ///
+    /// Every entry is released before the entry of the next key is
+    /// resolved, so that `f` observes the effects of its preceding calls.
+    pub fn entry_all<I, F>(&mut self, keys: I, mut f: F)
+    where
+        K: Ord + Clone,
+        I: IntoIterator<Item = K>,
+        F: FnMut(&K, Entry<'_, K, V>),
+    {
+        for key in keys {
+            let entry_key = key.clone();
+            f(&entry_key, self.entry(key));
+        }
+    }
+
+    /// Returns the index of the key and a mutable reference to its value,
+    /// inserting the default value first if the key is not present in the map.
+    pub fn get_or_insert_default_full(&mut self, key: K) -> (usize, &mut V)
+    where
+        K: Ord + Clone,
+        V: Default,
+    {
+        let entry = self.entry(key);
+        let index = entry.index();
+        (index, entry.or_default())
+    }
+
+    /// Returns the index of the key, inserting the default value first if
+    /// the key is not present in the map.
+    ///
+    /// Newly inserted keys receive sequential indices which makes this suitable
+    /// for interning keys where the index serves as the identifier of the key.
+    pub fn intern(&mut self, key: K) -> usize
+    where
+        K: Ord + Clone,
+        V: Default,
+    {
+        let (index, _value) = self.get_or_insert_default_full(key);
+        index
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map’s key type,
+    /// but the ordering on the borrowed form must match the ordering on the key type.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.key2slot
+            .get(key)
+            .map(|slot| &self.slots[slot.index()].value)
+    }
+
+    /// Returns a reference to the value corresponding to the key or `default`
+    /// if the key is not present in the map.
+    ///
+    /// Unlike [`Entry::or_insert`] this does not insert `default` into the map.
+    pub fn get_or<'a, Q>(&'a self, key: &Q, default: &'a V) -> &'a V
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map’s key type,
+    /// but the ordering on the borrowed form must match the ordering on the key type.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.key2slot
+            .get(key)
+            .map(|slot| &mut self.slots[slot.index()].value)
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the ordering
+    /// on the key type.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.key2slot
+            .get(key)
+            .map(|slot| self.slots[slot.index()].as_pair())
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key
+    /// as well as the unique index of the returned key-value pair.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the ordering
+    /// on the key type.
+    pub fn get_full<Q>(&self, key: &Q) -> Option<(usize, &K, &V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.key2slot.get(key).map(|slot| {
+            let index = slot.index();
+            let (key, value) = self.slots[index].as_pair();
+            (index, key, value)
+        })
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key
+    /// as well as the unique index of the returned key-value pair.
+    ///
+    /// The value is returned as exclusive reference while the key stays
+    /// shared in order to keep the key index of the map intact.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the ordering
+    /// on the key type.
+    pub fn get_full_mut<Q>(&mut self, key: &Q) -> Option<(usize, &K, &mut V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        let index = self.key2slot.get(key)?.index();
+        let (key, value) = self.slots[index].as_pair_mut();
+        Some((index, key, value))
+    }
+
+    /// Returns the unique index corresponding to the supplied key.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the ordering
+    /// on the key type.
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.key2slot.get(key).copied().map(SlotIndex::index)
+    }
+
+    /// Returns a shared reference to the key-value pair at the given index.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.slots.get(index).map(Slot::as_pair)
+    }
+
+    /// Returns a shared reference to the key-value pair at the given index.
+    ///
+    /// # Errors
+    ///
+    /// If `index` is out of bounds.
+    pub fn get_index_checked(&self, index: usize) -> Result<(&K, &V), IndexOutOfBounds> {
+        self.get_index(index).ok_or(IndexOutOfBounds {
+            index,
+            len: self.len(),
+        })
+    }
+
+    /// Returns an exclusive reference to the key-value pair at the given index.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.slots.get_mut(index).map(Slot::as_pair_mut)
+    }
+
+    /// Returns a shared reference to the first key-value pair in insertion order.
+    ///
+    /// Returns `None` if the map is empty.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.slots.first().map(Slot::as_pair)
+    }
+
+    /// Returns an exclusive reference to the first key-value pair in insertion order.
+    ///
+    /// Returns `None` if the map is empty.
+    pub fn first_mut(&mut self) -> Option<(&K, &mut V)> {
+        self.slots.first_mut().map(Slot::as_pair_mut)
+    }
+
+    /// Returns a shared reference to the last key-value pair in insertion order.
+    ///
+    /// Returns `None` if the map is empty.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.slots.last().map(Slot::as_pair)
+    }
+
+    /// Returns an exclusive reference to the last key-value pair in insertion order.
+    ///
+    /// Returns `None` if the map is empty.
+    pub fn last_mut(&mut self) -> Option<(&K, &mut V)> {
+        self.slots.last_mut().map(Slot::as_pair_mut)
+    }
+
+    /// Returns shared references to the key-value pairs within the given range of indices.
+    ///
+    /// Returns `None` if the range is out of bounds.
+    pub fn get_index_range<R>(&self, range: R) -> Option<Vec<(&K, &V)>>
+    where
+        R: RangeBounds<usize>,
+    {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.slots
+            .get(bounds)
+            .map(|slots| slots.iter().map(Slot::as_pair).collect())
+    }
+
+    /// Returns a view of the key-value pairs within the given range of indices
+    /// that allows to mutate their values.
+    ///
+    /// Returns `None` if the range is out of bounds.
+    pub fn get_range_mut<R>(&mut self, range: R) -> Option<SliceMut<'_, K, V>>
+    where
+        R: RangeBounds<usize>,
+    {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.slots.get_mut(bounds).map(|slots| SliceMut { slots })
+    }
+
+    /// Returns an iterator over all contiguous windows of length `size`
+    /// of the key-value pairs in insertion order.
+    ///
+    /// The windows overlap. If the map is shorter than `size` the iterator
+    /// yields no windows.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is zero.
+    pub fn windows(&self, size: usize) -> Windows<'_, K, V> {
+        Windows {
+            iter: self.slots.windows(size),
+        }
+    }
+
+    /// Returns an iterator over chunks of `size` key-value pairs in insertion order.
+    ///
+    /// The chunks do not overlap. If `size` does not divide the length of the
+    /// map the last chunk contains fewer than `size` key-value pairs.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is zero.
+    pub fn chunks(&self, size: usize) -> Chunks<'_, K, V> {
+        Chunks {
+            iter: self.slots.chunks(size),
+        }
+    }
+
+    /// Returns the index of the first key-value pair in insertion order
+    /// for which `f` returns `true`.
+    ///
+    /// Returns `None` if `f` returns `false` for all key-value pairs.
+    pub fn position<F>(&self, mut f: F) -> Option<usize>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.slots.iter().position(|slot| f(&slot.key, &slot.value))
+    }
+
+    /// Returns the index of the last key-value pair in insertion order
+    /// for which `f` returns `true`.
+    ///
+    /// Returns `None` if `f` returns `false` for all key-value pairs.
+    pub fn rposition<F>(&self, mut f: F) -> Option<usize>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.slots
+            .iter()
+            .rposition(|slot| f(&slot.key, &slot.value))
+    }
+
+    /// Returns the index of the first key-value pair in insertion order
+    /// whose value satisfies `f`.
+    ///
+    /// Returns `None` if no value satisfies `f`.
+    pub fn index_of_value<F>(&self, f: F) -> Option<usize>
+    where
+        F: FnMut(&V) -> bool,
+    {
+        self.values().position(f)
+    }
+
+    /// Returns the key of the first key-value pair in insertion order
+    /// whose value satisfies `f`.
+    ///
+    /// Returns `None` if no value satisfies `f`.
+    pub fn key_of_value<F>(&self, f: F) -> Option<&K>
+    where
+        F: FnMut(&V) -> bool,
+    {
+        self.index_of_value(f).map(|index| &self.slots[index].key)
+    }
+
+    /// Gets an iterator over the indices of all key-value pairs for which `f`
+    /// returns `true` in ascending order.
+    pub fn indices_where<'a, F>(&'a self, mut f: F) -> impl Iterator<Item = usize> + 'a
+    where
+        F: FnMut(&K, &V) -> bool + 'a,
+    {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(move |(_index, slot)| f(&slot.key, &slot.value))
+            .map(|(index, _slot)| index)
+    }
+
+    /// Binary searches the key-value pairs of the map with a comparator function.
+    ///
+    /// The comparator function should return an order code that indicates
+    /// whether its argument is `Less`, `Equal` or `Greater` the desired target.
+    ///
+    /// If the value is found then [`Result::Ok`] is returned, containing the
+    /// index of the matching key-value pair. If there are multiple matches,
+    /// then any one of the matches could be returned. If the value is not found
+    /// then [`Result::Err`] is returned, containing the index where a matching
+    /// key-value pair could be inserted while maintaining sorted order.
+    ///
+    /// # Note
+    ///
+    /// This operates on the insertion order of the map and not on the order of
+    /// its keys. Therefore the key-value pairs of the map must already be sorted
+    /// with respect to `f`, for example by a preceding sort using the same
+    /// comparator. Otherwise the returned result is unspecified and meaningless.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&K, &V) -> Ordering,
+    {
+        self.slots
+            .binary_search_by(|slot| f(&slot.key, &slot.value))
+    }
+
+    /// Gets an iterator over the entries of the map in insertion order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.slots.iter(),
+        }
+    }
+
+    /// Returns all entries of the map alongside their indices in insertion order.
+    pub fn enumerate_vec(&self) -> Vec<(usize, &K, &V)> {
+        self.iter()
+            .enumerate()
+            .map(|(index, (key, value))| (index, key, value))
+            .collect()
+    }
+
+    /// Gets an iterator over the entries of the map in insertion order that
+    /// stops at the first entry for which `f` returns `false`.
+    ///
+    /// This is a shorthand for `iter().take_while(..)` that does not require
+    /// to destructure the key-value pairs in the predicate.
+    pub fn iter_while<F>(&self, mut f: F) -> impl Iterator<Item = (&K, &V)>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.iter().take_while(move |(key, value)| f(key, value))
+    }
+
+    /// Gets an iterator over the entries of the map in ascending key order.
+    ///
+    /// This is independent of the insertion order of the entries.
+    pub fn sorted_iter(&self) -> SortedIter<'_, K, V> {
+        SortedIter {
+            iter: self.key2slot.values(),
+            slots: &self.slots,
+        }
+    }
+
+    /// Gets an iterator over the entries of the map in descending key order.
+    ///
+    /// This is independent of the insertion order of the entries.
+    pub fn iter_rev_sorted(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.sorted_iter().rev()
+    }
+
+    /// Consumes the map and returns an iterator over its entries in ascending key order.
+    ///
+    /// This is independent of the insertion order of the entries.
+    pub fn into_iter_sorted(self) -> impl Iterator<Item = (K, V)>
+    where
+        K: Ord,
+    {
+        let mut slots: Vec<Option<Slot<K, V>>> = self.slots.into_iter().map(Some).collect();
+        self.key2slot.into_values().map(move |slot| {
+            slots[slot.index()]
+                .take()
+                .map(Slot::into_pair)
+                .expect("IndexMap: slot has already been taken")
+        })
+    }
+
+    /// Returns references to all key-value pairs of the map sorted by `cmp`.
+    ///
+    /// The sort is stable and leaves the order of the map itself untouched.
+    pub fn to_vec_sorted_by<F>(&self, cmp: F) -> Vec<(&K, &V)>
+    where
+        F: FnMut(&(&K, &V), &(&K, &V)) -> Ordering,
+    {
+        let mut pairs: Vec<(&K, &V)> = self.iter().collect();
+        pairs.sort_by(cmp);
+        pairs
+    }
+
+    /// Groups consecutive keys in ascending key order by a derived group
+    /// and returns all groups alongside the number of keys in them.
+    ///
+    /// Keys that derive the same group but that are not consecutive in
+    /// ascending key order form separate groups.
+    pub fn group_runs_sorted<G, F>(&self, mut key: F) -> Vec<(G, usize)>
+    where
+        G: PartialEq,
+        F: FnMut(&K) -> G,
+    {
+        let mut runs: Vec<(G, usize)> = Vec::new();
+        for (k, _value) in self.sorted_iter() {
+            let group = key(k);
+            match runs.last_mut() {
+                Some((last, count)) if *last == group => *count += 1,
+                _ => runs.push((group, 1)),
+            }
+        }
+        runs
+    }
+
+    /// Folds all key-value pairs of the map in insertion order into an accumulator.
+    ///
+    /// This is a shorthand for `iter().fold(..)` that does not require
+    /// to destructure the key-value pairs in the closure.
+    pub fn fold<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, &K, &V) -> B,
+    {
+        self.iter()
+            .fold(init, move |acc, (key, value)| f(acc, key, value))
+    }
+
+    /// Folds all key-value pairs of the map in insertion order into an accumulator
+    /// as long as `f` returns successfully.
+    ///
+    /// Stops at the first error returned by `f` and returns it.
+    ///
+    /// # Note
+    ///
+    /// Unlike [`Iterator::try_fold`] this only supports `Result` since the `Try`
+    /// trait that abstracts over `Result` and `Option` is unstable. In order to
+    /// short-circuit on `None` convert the `Option` returned by `f` via
+    /// [`Option::ok_or`] and the returned `Result` via [`Result::ok`].
+    pub fn try_fold<B, E, F>(&self, init: B, mut f: F) -> Result<B, E>
+    where
+        F: FnMut(B, &K, &V) -> Result<B, E>,
+    {
+        self.iter()
+            .try_fold(init, move |acc, (key, value)| f(acc, key, value))
+    }
+
+    /// Gets a mutable iterator over the entries of the map in insertion order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            iter: self.slots.iter_mut(),
+        }
+    }
+
+    /// Gets an iterator over the keys and mutable values of the map in insertion order.
+    ///
+    /// This is the same as [`IndexMap::iter_mut`].
+    pub fn pairs_mut(&mut self) -> IterMut<'_, K, V> {
+        self.iter_mut()
+    }
+
+    /// Gets an iterator over the keys of the map in insertion order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: self.slots.iter(),
+        }
+    }
+
+    /// Gets an iterator over the values of the map in insertion order.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.slots.iter(),
+        }
+    }
+
+    /// Gets a mutable iterator over the values of the map in insertion order.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            iter: self.slots.iter_mut(),
+        }
+    }
+
+    /// Calls `f` on every value of the map in insertion order.
+    ///
+    /// # Note
+    ///
+    /// The values of the map are stored alongside their keys and therefore
+    /// cannot be exposed as a contiguous `&mut [V]` slice.
+    pub fn for_each_value_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut V),
+    {
+        self.values_mut().for_each(f)
+    }
+
+    /// Consumes the map and returns an iterator over its keys in insertion order.
+    pub fn into_keys(self) -> IntoKeys<K, V> {
+        IntoKeys {
+            iter: self.slots.into_iter(),
+        }
+    }
+
+    /// Consumes the map and returns an iterator over its values in insertion order.
+    pub fn into_values(self) -> IntoValues<K, V> {
+        IntoValues {
+            iter: self.slots.into_iter(),
+        }
+    }
+
+    /// Retains only the key-value pairs for which `keep` returns `true`.
+    ///
+    /// Visits the key-value pairs in insertion order and preserves the
+    /// insertion order of the retained key-value pairs.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        K: Ord,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let key2slot = &mut self.key2slot;
+        let mut index = 0;
+        self.slots.retain_mut(|slot| {
+            if !keep(&slot.key, &mut slot.value) {
+                key2slot.remove(&slot.key);
+                return false;
+            }
+            let slot_index = key2slot
+                .get_mut(&slot.key)
+                .expect("IndexMap: missing slot index for key");
+            *slot_index = SlotIndex::new(index);
+            index += 1;
+            true
+        });
+    }
+
+    /// Retains only the key-value pairs specified by the predicate and sorts
+    /// the retained key-value pairs by key.
+    ///
+    /// Visits the key-value pairs in ascending key order and removes all
+    /// pairs for which `f` returns `false`. Afterwards the retained pairs are
+    /// stored in ascending key order, so that a map that has been sorted by key
+    /// before stays sorted.
+    pub fn retain_sorted<F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut slots: Vec<Option<Slot<K, V>>> =
+            take(&mut self.slots).into_iter().map(Some).collect();
+        let retained = &mut self.slots;
+        retained.reserve(slots.len());
+        self.key2slot.retain(|_, slot_index| {
+            let mut slot = slots[slot_index.index()]
+                .take()
+                .expect("IndexMap: multiple keys refer to the same slot");
+            let keep = f(&slot.key, &mut slot.value);
+            if keep {
+                *slot_index = SlotIndex::new(retained.len());
+                retained.push(slot);
+            }
+            keep
+        });
+    }
+
+    /// Sorts the key-value pairs of the map by key.
+    ///
+    /// Afterwards the insertion order of the key-value pairs is their ascending key order.
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        self.retain_sorted(|_key, _value| true)
+    }
+
+    /// Sorts the key-value pairs of the map with the comparator function `cmp`.
+    ///
+    /// The sort is stable.
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        K: Ord,
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        self.slots
+            .sort_by(|a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        self.reindex(0..self.len());
+    }
+
+    /// Sorts the key-value pairs of the map with the comparator function `cmp`.
+    ///
+    /// Unlike [`IndexMap::sort_by`] the sort is unstable, i.e. key-value pairs
+    /// that compare equal may be reordered, but it does not allocate.
+    pub fn sort_unstable_by<F>(&mut self, mut cmp: F)
+    where
+        K: Ord,
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        self.slots
+            .sort_unstable_by(|a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        self.reindex(0..self.len());
+    }
+
+    /// Removes all key-value pairs for which `f` returns `true` and returns
+    /// an iterator over the removed key-value pairs.
+    ///
+    /// Visits the key-value pairs in ascending key order and yields the removed
+    /// key-value pairs in that order. The remaining key-value pairs keep their
+    /// insertion order.
+    ///
+    /// Unlike a lazy `extract_if` the key-value pairs are removed eagerly, even
+    /// if the returned iterator is not consumed.
+    pub fn extract_if_sorted<F>(&mut self, mut f: F) -> impl Iterator<Item = (K, V)>
+    where
+        K: Ord,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let slots = &mut self.slots;
+        let mut extracted = Vec::new();
+        self.key2slot.retain(|_, slot_index| {
+            let slot = &mut slots[slot_index.index()];
+            let extract = f(&slot.key, &mut slot.value);
+            if extract {
+                extracted.push(slot_index.index());
+            }
+            !extract
+        });
+        if extracted.is_empty() {
+            return Vec::new().into_iter();
+        }
+        let mut slots: Vec<Option<Slot<K, V>>> =
+            take(&mut self.slots).into_iter().map(Some).collect();
+        let removed: Vec<(K, V)> = extracted
+            .into_iter()
+            .map(|index| {
+                slots[index]
+                    .take()
+                    .map(Slot::into_pair)
+                    .expect("IndexMap: multiple keys refer to the same slot")
+            })
+            .collect();
+        self.slots = slots.into_iter().flatten().collect();
+        self.reindex(0..self.len());
+        removed.into_iter()
+    }
+
+    /// Retains only the key-value pairs whose keys are contained in `keys`.
+    ///
+    /// Preserves the insertion order of the retained key-value pairs.
+    pub fn retain_keys_in(&mut self, keys: &IndexSet<K>)
+    where
+        K: Ord,
+    {
+        self.retain(|key, _value| keys.contains(key))
+    }
+
+    /// Retains only the key-value pairs for which `f` returns `true` and returns
+    /// the removed key-value pairs as a new map.
+    ///
+    /// Both maps preserve the insertion order of their key-value pairs.
+    pub fn retain_split<F>(&mut self, mut f: F) -> IndexMap<K, V>
+    where
+        K: Ord,
+        F: FnMut(&K, &V) -> bool,
+    {
+        let mut removed = IndexMap::new();
+        for slot in take(&mut self.slots) {
+            if f(&slot.key, &slot.value) {
+                self.slots.push(slot);
+                continue;
+            }
+            let (key, _slot) = self
+                .key2slot
+                .remove_entry(&slot.key)
+                .expect("IndexMap: missing slot index for key");
+            removed
+                .key2slot
+                .insert(key, SlotIndex::new(removed.slots.len()));
+            removed.slots.push(slot);
+        }
+        self.reindex(0..self.len());
+        removed
+    }
+
+    /// Retains only the first `n` key-value pairs in insertion order for which `f`
+    /// returns `true`.
+    ///
+    /// Preserves the insertion order of the retained key-value pairs.
+    pub fn retain_first_n<F>(&mut self, n: usize, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&K, &V) -> bool,
+    {
+        let mut remaining = n;
+        self.retain(|key, value| {
+            if remaining == 0 || !f(key, value) {
+                return false;
+            }
+            remaining -= 1;
+            true
+        })
+    }
+
+    /// Consumes the map and returns a new map with the values transformed by `f`.
+    ///
+    /// Key-value pairs for which `f` returns `None` are dropped.
+    /// Preserves the insertion order of the remaining key-value pairs.
+    pub fn filter_map_values<W, F>(self, mut f: F) -> IndexMap<K, W>
+    where
+        K: Ord,
+        F: FnMut(&K, V) -> Option<W>,
+    {
+        let mut key2slot = self.key2slot;
+        let mut slots = Vec::with_capacity(self.slots.len());
+        for slot in self.slots {
+            let (key, value) = slot.into_pair();
+            match f(&key, value) {
+                Some(value) => {
+                    let slot_index = key2slot
+                        .get_mut(&key)
+                        .expect("IndexMap: missing slot index for key");
+                    *slot_index = SlotIndex::new(slots.len());
+                    slots.push(Slot::new(key, value));
+                }
+                None => {
+                    key2slot.remove(&key);
+                }
+            }
+        }
+        IndexMap {
+            key2slot,
+            slots,
+            sorted_hint: false,
+        }
+    }
+
+    /// Removes all key-value pairs whose values are equal to the value of an
+    /// earlier key-value pair in insertion order.
+    ///
+    /// Preserves the insertion order of the remaining key-value pairs.
+    /// Returns the number of removed key-value pairs.
+    ///
+    /// # Note
+    ///
+    /// Since values are only required to be comparable for equality this
+    /// takes O(n²) time in the worst case.
+    pub fn dedup_values(&mut self) -> usize
+    where
+        K: Ord,
+        V: Eq,
+    {
+        let mut kept: Vec<usize> = Vec::new();
+        let mut keep = Vec::with_capacity(self.len());
+        for (index, slot) in self.slots.iter().enumerate() {
+            let is_new = kept
+                .iter()
+                .all(|&kept| self.slots[kept].value != slot.value);
+            if is_new {
+                kept.push(index);
+            }
+            keep.push(is_new);
+        }
+        let removed = self.len() - kept.len();
+        if removed != 0 {
+            let mut keep = keep.into_iter();
+            self.retain(|_key, _value| keep.next().unwrap_or(true));
+        }
+        removed
+    }
+
+    /// Removes all key-value pairs whose keys are yielded by `keys`.
+    ///
+    /// Keys that are not contained in the map are ignored.
+    ///
+    /// Preserves the insertion order of the remaining key-value pairs.
+    /// Unlike repeated calls to [`IndexMap::shift_remove`] the remaining
+    /// key-value pairs are reindexed only once.
+    pub fn remove_all_keys<I>(&mut self, keys: I)
+    where
+        K: Ord,
+        I: IntoIterator<Item = K>,
+    {
+        let mut remove = Vec::new();
+        for key in keys {
+            if let Some(index) = self.get_index_of(&key) {
+                if remove.is_empty() {
+                    remove.resize(self.len(), false);
+                }
+                remove[index] = true;
+            }
+        }
+        if remove.is_empty() {
+            return;
+        }
+        let mut remove = remove.into_iter();
+        self.retain(|_key, _value| !remove.next().unwrap_or(false))
+    }
+
+    /// Overwrites the values of the map in insertion order with the values of `values`.
+    ///
+    /// Stops as soon as either the map or `values` runs out of items. This means
+    /// that trailing values of the map are left untouched if `values` is shorter
+    /// than the map and that surplus items of `values` are not consumed otherwise.
+    /// The keys of the map are never changed.
+    pub fn zip_values_mut<I>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        for (slot, value) in self.slots.iter_mut().zip(values) {
+            slot.value = value;
+        }
+    }
+
+    /// Clears the map, removing all elements.
+    pub fn clear(&mut self) {
+        self.key2slot.clear();
+        self.slots.clear();
+    }
+
+    /// Shortens the map, keeping the first `len` key-value pairs in insertion
+    /// order and dropping the rest.
+    ///
+    /// Like [`Vec::truncate`], this has no effect if `len` is greater than or
+    /// equal to the current length of the map.
+    pub fn truncate(&mut self, len: usize)
+    where
+        K: Ord,
+    {
+        if len >= self.len() {
+            return;
+        }
+        for slot in &self.slots[len..] {
+            self.key2slot.remove(&slot.key);
+        }
+        self.slots.truncate(len);
+    }
+
+    /// Splits the map into two at the given index.
+    ///
+    /// Returns a new map containing the key-value pairs from index `at` onward
+    /// in their original order while `self` retains the key-value pairs before `at`.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is greater than the length of the map.
+    pub fn split_off(&mut self, at: usize) -> Self
+    where
+        K: Ord,
+    {
+        let slots = self.slots.split_off(at);
+        let mut key2slot = BTreeMap::new();
+        for (index, slot) in slots.iter().enumerate() {
+            let (key, _slot) = self
+                .key2slot
+                .remove_entry(&slot.key)
+                .expect("IndexMap: missing slot index for key");
+            key2slot.insert(key, SlotIndex::new(index));
+        }
+        Self {
+            key2slot,
+            slots,
+            sorted_hint: false,
+        }
+    }
+
+    /// Converts the map into an array of its key-value pairs in insertion order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the unchanged map if its length is not equal to `N`.
+    pub fn try_into_pairs_array<const N: usize>(self) -> Result<[(K, V); N], Self> {
+        if self.len() != N {
+            return Err(self);
+        }
+        let pairs: Vec<(K, V)> = self.into_iter().collect();
+        Ok(pairs
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("the length of the map has been checked above")))
+    }
+
+    /// Extends the map with the longest prefix of `iter` with keys in strictly
+    /// ascending order that are all greater than the keys already in the map.
+    ///
+    /// The keys of the prefix are bulk-inserted into the key index at once if
+    /// there are at least as many of them as keys already in the key index.
+    /// Otherwise rebuilding the key index would cost more than inserting them
+    /// one by one.
+    ///
+    /// Returns the first key-value pair that violated the ordering, if any.
+    /// The violating key-value pair has not been inserted into the map.
+    fn extend_sorted<I>(&mut self, iter: &mut I) -> Option<(K, V)>
+    where
+        K: Ord + Clone,
+        I: Iterator<Item = (K, V)>,
+    {
+        let mut sorted = Vec::new();
+        let mut violation = None;
+        for (key, value) in iter.by_ref() {
+            let last = match sorted.last() {
+                Some((last, _)) => Some(last),
+                None => self.key2slot.last_key_value().map(|(last, _)| last),
+            };
+            if last.is_some_and(|last| *last >= key) {
+                violation = Some((key, value));
+                break;
+            }
+            sorted.push((key.clone(), SlotIndex::new(self.slots.len())));
+            self.slots.push(Slot::new(key, value));
+        }
+        if sorted.len() >= self.key2slot.len() {
+            self.key2slot.append(&mut sorted.into_iter().collect());
+        } else {
+            self.key2slot.extend(sorted);
+        }
+        violation
+    }
+
+    /// Inserts a key-value pair into the map at position `index`.
+    ///
+    /// If the map already had this key present, only its value is updated
+    /// and the old value is returned. The key-value pair is not moved.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds and the key is not yet present.
+    fn insert_at(&mut self, index: usize, key: K, value: V) -> Option<V>
+    where
+        K: Ord + Clone,
+    {
+        match self.key2slot.entry(key.clone()) {
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(SlotIndex::new(index));
+                self.slots.insert(index, Slot::new(key, value));
+                self.reindex(index + 1..self.len());
+                None
+            }
+            btree_map::Entry::Occupied(entry) => {
+                let index = entry.get().index();
+                Some(replace(&mut self.slots[index].value, value))
+            }
+        }
+    }
+
+    /// Removes the slot at `index` by swapping it with the last slot.
+    ///
+    /// The key of the removed slot must have been removed from `key2slot` already.
+    fn swap_remove_slot(&mut self, index: usize) -> Slot<K, V>
+    where
+        K: Ord,
+    {
+        let removed = self.slots.swap_remove(index);
+        if index < self.slots.len() {
+            self.reindex(index..index + 1);
+        }
+        removed
+    }
+
+    /// Removes the slot at `index` by shifting all following slots.
+    ///
+    /// The key of the removed slot must have been removed from `key2slot` already.
+    fn shift_remove_slot(&mut self, index: usize) -> Slot<K, V>
+    where
+        K: Ord,
+    {
+        let removed = self.slots.remove(index);
+        self.reindex(index..self.len());
+        removed
+    }
+
+    /// Updates the slot indices of all slots within `range`.
+    ///
+    /// This needs to be called after slots have been moved within `slots`.
+    fn reindex(&mut self, range: Range<usize>)
+    where
+        K: Ord,
+    {
+        for index in range {
+            let slot_index = self
+                .key2slot
+                .get_mut(&self.slots[index].key)
+                .expect("IndexMap: missing slot index for key");
+            *slot_index = SlotIndex::new(index);
+        }
+    }
+}
+
+impl<'a, K, Q, V> Index<&'a Q> for IndexMap<K, V>
+where
+    K: Borrow<Q> + Ord,
+    Q: ?Sized + Ord,
+{
+    type Output = V;
+
+    fn index(&self, key: &'a Q) -> &Self::Output {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V> Index<usize> for IndexMap<K, V> {
+    type Output = V;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        let (_key, value) = self
+            .get_index(index)
+            .expect("IndexMap: index out of bounds");
+        value
+    }
+}
+
+impl<K, V> IndexMut<usize> for IndexMap<K, V> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let (_key, value) = self
             .get_index_mut(index)
             .expect("IndexMap: index out of bounds");
         value
     }
-}
+}
+
+impl<'a, K, V> Extend<(&'a K, &'a V)> for IndexMap<K, V>
+where
+    K: Ord + Copy,
+    V: Copy,
+{
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = (&'a K, &'a V)>,
+    {
+        self.extend(iter.into_iter().map(|(key, value)| (*key, *value)))
+    }
+}
+
+impl<K, V> Extend<(K, V)> for IndexMap<K, V>
+where
+    K: Ord + Clone,
+{
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = (K, V)>,
+    {
+        let mut iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        if self.sorted_hint {
+            if let Some((key, value)) = self.extend_sorted(&mut iter) {
+                self.insert(key, value);
+            }
+        }
+        iter.for_each(move |(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for IndexMap<K, V>
+where
+    K: Ord + Clone,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        let mut map = IndexMap::with_capacity_and_sorted_hint(iter.size_hint().0);
+        map.extend(iter);
+        map.sorted_hint = false;
+        map
+    }
+}
+
+impl<K, V, const N: usize> From<[(K, V); N]> for IndexMap<K, V>
+where
+    K: Ord + Clone,
+{
+    fn from(items: [(K, V); N]) -> Self {
+        items.into_iter().collect()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a IndexMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut IndexMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V> IntoIterator for IndexMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.slots.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the entries of an [`IndexMap`].
+///
+/// This `struct` is created by the [`iter`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`iter`]: IndexMap::iter
+#[derive(Debug, Clone)]
+pub struct Iter<'a, K, V> {
+    iter: SliceIter<'a, Slot<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(Slot::as_pair)
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(Slot::as_pair)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+/// A mutable iterator over the entries of an [`IndexMap`].
+///
+/// This `struct` is created by the [`iter_mut`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`iter_mut`]: IndexMap::iter_mut
+#[derive(Debug)]
+pub struct IterMut<'a, K, V> {
+    iter: SliceIterMut<'a, Slot<K, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 
-impl<'a, K, V> Extend<(&'a K, &'a V)> for IndexMap<K, V>
-where
-    K: Ord + Copy,
-    V: Copy,
-{
-    fn extend<T>(&mut self, iter: T)
-    where
-        T: IntoIterator<Item = (&'a K, &'a V)>,
-    {
-        self.extend(iter.into_iter().map(|(key, value)| (*key, *value)))
+    fn count(self) -> usize {
+        self.iter.count()
     }
-}
 
-impl<K, V> Extend<(K, V)> for IndexMap<K, V>
-where
-    K: Ord + Clone,
-{
-    fn extend<T>(&mut self, iter: T)
-    where
-        T: IntoIterator<Item = (K, V)>,
-    {
-        iter.into_iter().for_each(move |(k, v)| {
-            self.insert(k, v);
-        });
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(Slot::as_pair_mut)
     }
 }
 
-impl<K, V> FromIterator<(K, V)> for IndexMap<K, V>
-where
-    K: Ord + Clone,
-{
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = (K, V)>,
-    {
-        let mut map = IndexMap::new();
-        map.extend(iter);
-        map
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(Slot::as_pair_mut)
     }
 }
 
-impl<K, V, const N: usize> From<[(K, V); N]> for IndexMap<K, V>
-where
-    K: Ord + Clone,
-{
-    fn from(items: [(K, V); N]) -> Self {
-        items.into_iter().collect()
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
     }
 }
 
-impl<'a, K, V> IntoIterator for &'a IndexMap<K, V> {
+impl<'a, K, V> FusedIterator for IterMut<'a, K, V> {}
+
+/// An iterator over the entries of an [`IndexMap`] in ascending key order.
+///
+/// This `struct` is created by the [`sorted_iter`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`sorted_iter`]: IndexMap::sorted_iter
+#[derive(Debug, Clone)]
+pub struct SortedIter<'a, K, V> {
+    iter: btree_map::Values<'a, K, SlotIndex>,
+    slots: &'a [Slot<K, V>],
+}
+
+impl<'a, K, V> Iterator for SortedIter<'a, K, V> {
     type Item = (&'a K, &'a V);
-    type IntoIter = Iter<'a, K, V>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slots = self.slots;
+        self.iter.next().map(|slot| slots[slot.index()].as_pair())
     }
 }
 
-impl<'a, K, V> IntoIterator for &'a mut IndexMap<K, V> {
-    type Item = (&'a K, &'a mut V);
-    type IntoIter = IterMut<'a, K, V>;
+impl<'a, K, V> DoubleEndedIterator for SortedIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let slots = self.slots;
+        self.iter
+            .next_back()
+            .map(|slot| slots[slot.index()].as_pair())
+    }
+}
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter_mut()
+impl<'a, K, V> ExactSizeIterator for SortedIter<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
     }
 }
 
-impl<K, V> IntoIterator for IndexMap<K, V> {
+impl<'a, K, V> FusedIterator for SortedIter<'a, K, V> {}
+
+/// An owning iterator over the entries of a [`IndexMap`].
+///
+/// This `struct` is created by the [`into_iter`] method on [`IndexMap`]
+/// (provided by the [`IntoIterator`] trait). See its documentation for more.
+///
+/// [`into_iter`]: IntoIterator::into_iter
+/// [`IntoIterator`]: core::iter::IntoIterator
+#[derive(Debug)]
+pub struct IntoIter<K, V> {
+    iter: VecIntoIter<Slot<K, V>>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
     type Item = (K, V);
-    type IntoIter = IntoIter<K, V>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        IntoIter {
-            iter: self.slots.into_iter(),
-        }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(Slot::into_pair)
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(Slot::into_pair)
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+/// An owning iterator over the keys of an [`IndexMap`].
+///
+/// This `struct` is created by the [`into_keys`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`into_keys`]: IndexMap::into_keys
+#[derive(Debug)]
+pub struct IntoKeys<K, V> {
+    iter: VecIntoIter<Slot<K, V>>,
+}
+
+impl<K, V> Iterator for IntoKeys<K, V> {
+    type Item = K;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(Slot::into_key)
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoKeys<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(Slot::into_key)
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoKeys<K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> FusedIterator for IntoKeys<K, V> {}
+
+/// An owning iterator over the values of an [`IndexMap`].
+///
+/// This `struct` is created by the [`into_values`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`into_values`]: IndexMap::into_values
+#[derive(Debug)]
+pub struct IntoValues<K, V> {
+    iter: VecIntoIter<Slot<K, V>>,
+}
+
+impl<K, V> Iterator for IntoValues<K, V> {
+    type Item = V;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(Slot::into_value)
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoValues<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(Slot::into_value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoValues<K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> FusedIterator for IntoValues<K, V> {}
+
+/// An iterator over the keys of an [`IndexMap`].
+///
+/// This `struct` is created by the [`keys`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`keys`]: IndexMap::keys
+#[derive(Debug, Clone)]
+pub struct Keys<'a, K, V> {
+    iter: SliceIter<'a, Slot<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(Slot::key)
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(Slot::key)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a, K, V> FusedIterator for Keys<'a, K, V> {}
+
+/// An iterator over the values of an [`IndexMap`].
+///
+/// This `struct` is created by the [`values`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`values`]: IndexMap::values
+#[derive(Debug, Clone)]
+pub struct Values<'a, K, V> {
+    iter: SliceIter<'a, Slot<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(Slot::value)
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(Slot::value)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
     }
 }
 
-/// An iterator over the entries of an [`IndexMap`].
+impl<'a, K, V> FusedIterator for Values<'a, K, V> {}
+
+/// An iterator over the values of an [`IndexMap`].
 ///
-/// This `struct` is created by the [`iter`] method on [`IndexMap`]. See its
+/// This `struct` is created by the [`values`] method on [`IndexMap`]. See its
 /// documentation for more.
 ///
-/// [`iter`]: IndexMap::iter
-#[derive(Debug, Clone)]
-pub struct Iter<'a, K, V> {
-    iter: SliceIter<'a, Slot<K, V>>,
+/// [`values`]: IndexMap::values
+#[derive(Debug)]
+pub struct ValuesMut<'a, K, V> {
+    iter: SliceIterMut<'a, Slot<K, V>>,
 }
 
-impl<'a, K, V> Iterator for Iter<'a, K, V> {
-    type Item = (&'a K, &'a V);
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.iter.size_hint()
@@ -436,120 +2423,329 @@ impl<'a, K, V> Iterator for Iter<'a, K, V> {
     }
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(Slot::as_pair)
+        self.iter.next().map(Slot::value_mut)
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back().map(Slot::as_pair)
+        self.iter.next_back().map(Slot::value_mut)
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+impl<'a, K, V> FusedIterator for ValuesMut<'a, K, V> {}
 
-/// A mutable iterator over the entries of an [`IndexMap`].
+/// The differences between two [`IndexMap`]s.
 ///
-/// This `struct` is created by the [`iter_mut`] method on [`IndexMap`]. See its
+/// This `struct` is created by the [`diff`] method on [`IndexMap`]. See its
 /// documentation for more.
 ///
-/// [`iter_mut`]: IndexMap::iter_mut
+/// [`diff`]: IndexMap::diff
 #[derive(Debug)]
-pub struct IterMut<'a, K, V> {
-    iter: SliceIterMut<'a, Slot<K, V>>,
+pub struct MapDiff<'a, K, V> {
+    /// The map that is treated as the old state.
+    old: &'a IndexMap<K, V>,
+    /// The map that is treated as the new state.
+    new: &'a IndexMap<K, V>,
 }
 
-impl<'a, K, V> Iterator for IterMut<'a, K, V> {
-    type Item = (&'a K, &'a mut V);
+impl<'a, K, V> MapDiff<'a, K, V>
+where
+    K: Ord,
+    V: PartialEq,
+{
+    /// Gets an iterator over the key-value pairs that are only present in the old map.
+    ///
+    /// The key-value pairs are yielded in the insertion order of the old map.
+    pub fn removed(&self) -> impl Iterator<Item = (&'a K, &'a V)> + 'a {
+        let new = self.new;
+        self.old
+            .iter()
+            .filter(move |(key, _)| !new.contains_key(key))
+    }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.iter.size_hint()
+    /// Gets an iterator over the key-value pairs that are only present in the new map.
+    ///
+    /// The key-value pairs are yielded in the insertion order of the new map.
+    pub fn added(&self) -> impl Iterator<Item = (&'a K, &'a V)> + 'a {
+        let old = self.old;
+        self.new
+            .iter()
+            .filter(move |(key, _)| !old.contains_key(key))
     }
 
-    fn count(self) -> usize {
-        self.iter.count()
+    /// Gets an iterator over the keys that are present in both maps with
+    /// differing values alongside their old and new values.
+    ///
+    /// The keys are yielded in the insertion order of the old map.
+    pub fn changed(&self) -> impl Iterator<Item = (&'a K, &'a V, &'a V)> + 'a {
+        let new = self.new;
+        self.old.iter().filter_map(move |(key, old)| {
+            new.get(key)
+                .filter(|new| old != *new)
+                .map(|new| (key, old, new))
+        })
     }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(Slot::as_pair_mut)
+    /// Returns `true` if both maps contain the same key-value pairs.
+    ///
+    /// The insertion order of the key-value pairs is not compared.
+    pub fn is_empty(&self) -> bool {
+        self.removed().next().is_none()
+            && self.added().next().is_none()
+            && self.changed().next().is_none()
     }
-}
 
-impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back().map(Slot::as_pair_mut)
+    /// Returns an owned [`MapPatch`] that transforms the old map into the new map.
+    ///
+    /// See [`IndexMap::apply_patch`] for how the patch is applied.
+    pub fn to_patch(&self) -> MapPatch<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        MapPatch {
+            removals: self.removed().map(|(key, _)| key.clone()).collect(),
+            additions: self
+                .added()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            changes: self
+                .changed()
+                .map(|(key, _, new)| (key.clone(), new.clone()))
+                .collect(),
+        }
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {
-    fn len(&self) -> usize {
-        self.iter.len()
+/// An owned set of changes that can be applied to an [`IndexMap`].
+///
+/// This `struct` is created by the [`to_patch`] method on [`MapDiff`] and
+/// applied by the [`apply_patch`] method on [`IndexMap`]. See their
+/// documentation for more.
+///
+/// [`to_patch`]: MapDiff::to_patch
+/// [`apply_patch`]: IndexMap::apply_patch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapPatch<K, V> {
+    /// The keys to remove.
+    removals: Vec<K>,
+    /// The key-value pairs to append.
+    additions: Vec<(K, V)>,
+    /// The keys whose values to replace alongside their new values.
+    changes: Vec<(K, V)>,
+}
+
+impl<K, V> MapPatch<K, V> {
+    /// Returns the keys that are removed by the patch.
+    pub fn removals(&self) -> &[K] {
+        &self.removals
+    }
+
+    /// Returns the key-value pairs that are appended by the patch.
+    pub fn additions(&self) -> &[(K, V)] {
+        &self.additions
+    }
+
+    /// Returns the keys whose values are replaced by the patch alongside their new values.
+    pub fn changes(&self) -> &[(K, V)] {
+        &self.changes
+    }
+
+    /// Returns `true` if the patch does not change anything.
+    pub fn is_empty(&self) -> bool {
+        self.removals.is_empty() && self.additions.is_empty() && self.changes.is_empty()
     }
 }
 
-impl<'a, K, V> FusedIterator for IterMut<'a, K, V> {}
+/// Statistics about the internal state of an [`IndexMap`].
+///
+/// This `struct` is created by the [`stats`] method on [`IndexMap`]. See its
+/// documentation for more.
+///
+/// [`stats`]: IndexMap::stats
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MapStats {
+    /// The number of key-value pairs in the map.
+    pub len: usize,
+    /// The number of key-value pairs the map can hold without reallocating.
+    pub slots_capacity: usize,
+    /// The number of keys stored in the key index.
+    pub key2slot_len: usize,
+}
 
-/// An owning iterator over the entries of a [`IndexMap`].
+/// A view into a contiguous range of key-value pairs of an [`IndexMap`]
+/// that allows to mutate their values.
 ///
-/// This `struct` is created by the [`into_iter`] method on [`IndexMap`]
-/// (provided by the [`IntoIterator`] trait). See its documentation for more.
+/// The keys are only accessible by shared reference since mutating them
+/// would invalidate the key index of the map.
 ///
-/// [`into_iter`]: IntoIterator::into_iter
-/// [`IntoIterator`]: core::iter::IntoIterator
+/// This `struct` is created by the [`get_range_mut`] method on [`IndexMap`].
+/// See its documentation for more.
+///
+/// [`get_range_mut`]: IndexMap::get_range_mut
 #[derive(Debug)]
-pub struct IntoIter<K, V> {
-    iter: VecIntoIter<Slot<K, V>>,
+pub struct SliceMut<'a, K, V> {
+    slots: &'a mut [Slot<K, V>],
 }
 
-impl<K, V> Iterator for IntoIter<K, V> {
-    type Item = (K, V);
+impl<'a, K, V> SliceMut<'a, K, V> {
+    /// Returns the number of key-value pairs in the view.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.iter.size_hint()
+    /// Returns `true` if the view contains no key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    fn count(self) -> usize {
-        self.iter.count()
+    /// Returns a shared reference to the key-value pair at the given index
+    /// relative to the start of the view.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.slots.get(index).map(Slot::as_pair)
     }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(Slot::into_pair)
+    /// Returns an exclusive reference to the key-value pair at the given index
+    /// relative to the start of the view.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.slots.get_mut(index).map(Slot::as_pair_mut)
+    }
+
+    /// Gets an iterator over the entries of the view in insertion order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.slots.iter(),
+        }
+    }
+
+    /// Gets a mutable iterator over the entries of the view in insertion order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            iter: self.slots.iter_mut(),
+        }
+    }
+
+    /// Gets a mutable iterator over the values of the view in insertion order.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            iter: self.slots.iter_mut(),
+        }
     }
 }
 
-impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back().map(Slot::into_pair)
+impl<'a, K, V> IntoIterator for SliceMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IterMut {
+            iter: self.slots.iter_mut(),
+        }
     }
 }
 
-impl<K, V> ExactSizeIterator for IntoIter<K, V> {
-    fn len(&self) -> usize {
-        self.iter.len()
+/// A view into a contiguous range of key-value pairs of an [`IndexMap`].
+///
+/// This `struct` is yielded by the [`Windows`] and [`Chunks`] iterators.
+#[derive(Debug)]
+pub struct EntrySlice<'a, K, V> {
+    slots: &'a [Slot<K, V>],
+}
+
+impl<'a, K, V> Clone for EntrySlice<'a, K, V> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
 
-impl<K, V> FusedIterator for IntoIter<K, V> {}
+impl<'a, K, V> Copy for EntrySlice<'a, K, V> {}
 
-/// An iterator over the values of an [`IndexMap`].
+impl<'a, K, V> EntrySlice<'a, K, V> {
+    /// Returns the number of key-value pairs in the view.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns `true` if the view contains no key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a shared reference to the key-value pair at the given index
+    /// relative to the start of the view.
+    pub fn get_index(&self, index: usize) -> Option<(&'a K, &'a V)> {
+        self.slots.get(index).map(Slot::as_pair)
+    }
+
+    /// Returns the first key-value pair of the view.
+    pub fn first(&self) -> Option<(&'a K, &'a V)> {
+        self.slots.first().map(Slot::as_pair)
+    }
+
+    /// Returns the last key-value pair of the view.
+    pub fn last(&self) -> Option<(&'a K, &'a V)> {
+        self.slots.last().map(Slot::as_pair)
+    }
+
+    /// Gets an iterator over the entries of the view in insertion order.
+    pub fn iter(&self) -> Iter<'a, K, V> {
+        Iter {
+            iter: self.slots.iter(),
+        }
+    }
+
+    /// Gets an iterator over the keys of the view in insertion order.
+    pub fn keys(&self) -> Keys<'a, K, V> {
+        Keys {
+            iter: self.slots.iter(),
+        }
+    }
+
+    /// Gets an iterator over the values of the view in insertion order.
+    pub fn values(&self) -> Values<'a, K, V> {
+        Values {
+            iter: self.slots.iter(),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for EntrySlice<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over overlapping windows of the entries of an [`IndexMap`].
 ///
-/// This `struct` is created by the [`values`] method on [`IndexMap`]. See its
+/// This `struct` is created by the [`windows`] method on [`IndexMap`]. See its
 /// documentation for more.
 ///
-/// [`values`]: IndexMap::values
-#[derive(Debug, Clone)]
-pub struct Values<'a, K, V> {
-    iter: SliceIter<'a, Slot<K, V>>,
+/// [`windows`]: IndexMap::windows
+#[derive(Debug)]
+pub struct Windows<'a, K, V> {
+    iter: SliceWindows<'a, Slot<K, V>>,
 }
 
-impl<'a, K, V> Iterator for Values<'a, K, V> {
-    type Item = &'a V;
+impl<'a, K, V> Clone for Windows<'a, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Windows<'a, K, V> {
+    type Item = EntrySlice<'a, K, V>;
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.iter.size_hint()
@@ -560,37 +2756,45 @@ impl<'a, K, V> Iterator for Values<'a, K, V> {
     }
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(Slot::value)
+        self.iter.next().map(|slots| EntrySlice { slots })
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+impl<'a, K, V> DoubleEndedIterator for Windows<'a, K, V> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back().map(Slot::value)
+        self.iter.next_back().map(|slots| EntrySlice { slots })
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {
+impl<'a, K, V> ExactSizeIterator for Windows<'a, K, V> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<'a, K, V> FusedIterator for Values<'a, K, V> {}
+impl<'a, K, V> FusedIterator for Windows<'a, K, V> {}
 
-/// An iterator over the values of an [`IndexMap`].
+/// An iterator over non-overlapping chunks of the entries of an [`IndexMap`].
 ///
-/// This `struct` is created by the [`values`] method on [`IndexMap`]. See its
+/// This `struct` is created by the [`chunks`] method on [`IndexMap`]. See its
 /// documentation for more.
 ///
-/// [`values`]: IndexMap::values
+/// [`chunks`]: IndexMap::chunks
 #[derive(Debug)]
-pub struct ValuesMut<'a, K, V> {
-    iter: SliceIterMut<'a, Slot<K, V>>,
+pub struct Chunks<'a, K, V> {
+    iter: SliceChunks<'a, Slot<K, V>>,
 }
 
-impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
-    type Item = &'a mut V;
+impl<'a, K, V> Clone for Chunks<'a, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Chunks<'a, K, V> {
+    type Item = EntrySlice<'a, K, V>;
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.iter.size_hint()
@@ -601,23 +2805,23 @@ impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
     }
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(Slot::value_mut)
+        self.iter.next().map(|slots| EntrySlice { slots })
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+impl<'a, K, V> DoubleEndedIterator for Chunks<'a, K, V> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back().map(Slot::value_mut)
+        self.iter.next_back().map(|slots| EntrySlice { slots })
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {
+impl<'a, K, V> ExactSizeIterator for Chunks<'a, K, V> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<'a, K, V> FusedIterator for ValuesMut<'a, K, V> {}
+impl<'a, K, V> FusedIterator for Chunks<'a, K, V> {}
 
 /// A view into a single entry in a map, which may either be vacant or occupied.
 ///
@@ -657,6 +2861,25 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
         }
     }
 
+    /// Ensures a value is in the entry by inserting the result
+    /// of the fallible default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Errors
+    ///
+    /// If the entry is vacant and the default function fails.
+    /// In this case the map is left unchanged.
+    pub fn or_try_insert_with<E, F>(self, default: F) -> Result<&'a mut V, E>
+    where
+        K: Clone,
+        F: FnOnce() -> Result<V, E>,
+    {
+        match self {
+            Self::Occupied(entry) => Ok(entry.into_mut()),
+            Self::Vacant(entry) => default().map(|value| entry.insert(value)),
+        }
+    }
+
     /// Ensures a value is in the entry by inserting,
     /// if empty, the result of the default function.
     ///
@@ -688,6 +2911,14 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
         }
     }
 
+    /// Returns the index where the key-value pair exists or will be inserted.
+    pub fn index(&self) -> usize {
+        match *self {
+            Self::Occupied(ref entry) => entry.index(),
+            Self::Vacant(ref entry) => entry.index(),
+        }
+    }
+
     /// Provides in-place mutable access to an occupied entry
     /// before any potential inserts into the map.
     pub fn and_modify<F>(self, f: F) -> Self
@@ -702,6 +2933,29 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
             Self::Vacant(entry) => Self::Vacant(entry),
         }
     }
+
+    /// Provides in-place mutable access to an occupied entry and removes
+    /// the entry from the map if `f` returns `true`.
+    ///
+    /// Returns `None` if the entry has been removed and the entry otherwise.
+    ///
+    /// The entry is removed via [`OccupiedEntry::swap_remove`] which
+    /// **perturbs the position of what used to be the last key-value pair!**
+    pub fn and_modify_or_remove<F>(self, f: F) -> Option<Self>
+    where
+        F: FnOnce(&mut V) -> bool,
+    {
+        match self {
+            Self::Occupied(mut entry) => {
+                if f(entry.get_mut()) {
+                    entry.swap_remove();
+                    return None;
+                }
+                Some(Self::Occupied(entry))
+            }
+            Self::Vacant(entry) => Some(Self::Vacant(entry)),
+        }
+    }
 }
 
 impl<'a, K, V> Entry<'a, K, V>
@@ -734,10 +2988,10 @@ where
 
 /// A view into a vacant entry in an [`IndexMap`]. It is part of the [`Entry`] `enum`.
 pub struct VacantEntry<'a, K, V> {
-    /// The underlying vacant entry.
-    vacant: btree_map::VacantEntry<'a, K, SlotIndex>,
-    /// The vector that stores all slots.
-    slots: &'a mut Vec<Slot<K, V>>,
+    /// The map that the entry belongs to.
+    map: &'a mut IndexMap<K, V>,
+    /// The key that is not yet present in the map.
+    key: K,
 }
 
 impl<'a, K, V> VacantEntry<'a, K, V>
@@ -746,12 +3000,17 @@ where
 {
     /// Gets a reference to the key that would be used when inserting a value through the VacantEntry.
     pub fn key(&self) -> &K {
-        self.vacant.key()
+        &self.key
+    }
+
+    /// Returns the index where the key-value pair will be inserted.
+    pub fn index(&self) -> usize {
+        self.map.len()
     }
 
     /// Take ownership of the key.
     pub fn into_key(self) -> K {
-        self.vacant.into_key()
+        self.key
     }
 
     /// Sets the value of the entry with the `VacantEntry`’s key,
@@ -760,11 +3019,12 @@ where
     where
         K: Clone,
     {
-        let index = self.slots.len();
-        let key = self.vacant.key().clone();
-        self.vacant.insert(SlotIndex(index));
-        self.slots.push(Slot::new(key, value));
-        &mut self.slots[index].value
+        let index = self.map.slots.len();
+        self.map
+            .key2slot
+            .insert(self.key.clone(), SlotIndex::new(index));
+        self.map.slots.push(Slot::new(self.key, value));
+        &mut self.map.slots[index].value
     }
 }
 
@@ -781,10 +3041,10 @@ where
 
 /// A view into an occupied entry in a [`IndexMap`]. It is part of the [`Entry`] `enum`.
 pub struct OccupiedEntry<'a, K, V> {
-    /// The underlying occupied entry.
-    occupied: btree_map::OccupiedEntry<'a, K, SlotIndex>,
-    /// The vector that stores all slots.
-    slots: &'a mut Vec<Slot<K, V>>,
+    /// The map that the entry belongs to.
+    map: &'a mut IndexMap<K, V>,
+    /// The index of the slot of the entry.
+    index: usize,
 }
 
 impl<'a, K, V> OccupiedEntry<'a, K, V>
@@ -793,13 +3053,17 @@ where
 {
     /// Gets a reference to the key in the entry.
     pub fn key(&self) -> &K {
-        self.occupied.key()
+        &self.map.slots[self.index].key
+    }
+
+    /// Returns the index of the key-value pair of the entry.
+    pub fn index(&self) -> usize {
+        self.index
     }
 
     /// Gets a reference to the value in the entry.
     pub fn get(&self) -> &V {
-        let index = self.occupied.get().index();
-        &self.slots[index].value
+        &self.map.slots[self.index].value
     }
 
     /// Gets a mutable reference to the value in the entry.
@@ -809,8 +3073,7 @@ where
     ///
     /// [`into_mut`]: OccupiedEntry::into_mut
     pub fn get_mut(&mut self) -> &mut V {
-        let index = self.occupied.get().index();
-        &mut self.slots[index].value
+        &mut self.map.slots[self.index].value
     }
 
     /// Converts the entry into a mutable reference to its value.
@@ -819,21 +3082,55 @@ where
     ///
     /// [`get_mut`]: OccupiedEntry::get_mut
     pub fn into_mut(self) -> &'a mut V {
-        let index = self.occupied.get().index();
-        &mut self.slots[index].value
+        &mut self.map.slots[self.index].value
     }
 
     /// Sets the value of the entry with the `OccupiedEntry`’s key,
     /// and returns the entry’s old value.
-    pub fn insert(&mut self, value: V) -> V
-    where
-        K: Clone,
-    {
-        let index = self.occupied.get().index();
-        let key = self.key().clone();
-        let new_slot = Slot::new(key, value);
-        let old_slot = replace(&mut self.slots[index], new_slot);
-        old_slot.value
+    pub fn insert(&mut self, value: V) -> V {
+        replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map and returns its value.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    pub fn swap_remove(self) -> V {
+        let (_key, value) = self.swap_remove_entry();
+        value
+    }
+
+    /// Removes the entry from the map and returns its key and value.
+    ///
+    /// Like [`Vec::swap_remove`], the key-value pair is removed by swapping it with
+    /// the last key-value pair of the map and popping it off.
+    /// **This perturbs the position of what used to be the last key-value pair!**
+    pub fn swap_remove_entry(self) -> (K, V) {
+        self.map
+            .swap_remove_entry_index(self.index)
+            .expect("IndexMap: occupied entry index out of bounds")
+    }
+
+    /// Removes the entry from the map and returns its value.
+    ///
+    /// Like [`Vec::remove`], the key-value pair is removed by shifting all of the
+    /// key-value pairs that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those key-value pairs!**
+    pub fn shift_remove(self) -> V {
+        let (_key, value) = self.shift_remove_entry();
+        value
+    }
+
+    /// Removes the entry from the map and returns its key and value.
+    ///
+    /// Like [`Vec::remove`], the key-value pair is removed by shifting all of the
+    /// key-value pairs that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those key-value pairs!**
+    pub fn shift_remove_entry(self) -> (K, V) {
+        let key = &self.map.slots[self.index].key;
+        self.map.key2slot.remove(key);
+        self.map.shift_remove_slot(self.index).into_pair()
     }
 }
 
@@ -0,0 +1,1606 @@
+use super::*;
+use core::cell::Cell;
+
+/// Asserts that the key index of `map` agrees with the positions of its key-value pairs.
+fn assert_consistent<K, V>(map: &IndexMap<K, V>)
+where
+    K: Ord + fmt::Debug,
+{
+    assert_eq!(map.key2slot.len(), map.slots.len());
+    for (index, slot) in map.slots.iter().enumerate() {
+        let slot_index = map.key2slot.get(&slot.key).map(|slot| slot.index());
+        assert_eq!(
+            slot_index,
+            Some(index),
+            "wrong slot index for {:?}",
+            slot.key
+        );
+    }
+}
+
+/// Returns the keys of `map` in insertion order.
+fn keys<K, V>(map: &IndexMap<K, V>) -> Vec<K>
+where
+    K: Copy,
+{
+    map.iter().map(|(key, _value)| *key).collect()
+}
+
+#[test]
+fn binary_search_by_finds_hits_and_insertion_points() {
+    let map: IndexMap<i32, char> = [(1, 'a'), (3, 'b'), (5, 'c'), (7, 'd')].into();
+    assert_eq!(map.binary_search_by(|key, _| key.cmp(&1)), Ok(0));
+    assert_eq!(map.binary_search_by(|key, _| key.cmp(&5)), Ok(2));
+    assert_eq!(map.binary_search_by(|key, _| key.cmp(&7)), Ok(3));
+    assert_eq!(map.binary_search_by(|key, _| key.cmp(&0)), Err(0));
+    assert_eq!(map.binary_search_by(|key, _| key.cmp(&4)), Err(2));
+    assert_eq!(map.binary_search_by(|key, _| key.cmp(&8)), Err(4));
+}
+
+#[test]
+fn insert_before_and_after_pivots() {
+    let mut map: IndexMap<i32, i32> = [(1, 10), (2, 20), (3, 30)].into();
+    assert_eq!(map.insert_before(&1, 0, 0), Ok(None));
+    assert_eq!(keys(&map), [0, 1, 2, 3]);
+    assert_eq!(map.insert_after(&3, 4, 40), Ok(None));
+    assert_eq!(keys(&map), [0, 1, 2, 3, 4]);
+    assert_eq!(map.insert_before(&2, 5, 50), Ok(None));
+    assert_eq!(map.insert_after(&2, 6, 60), Ok(None));
+    assert_eq!(keys(&map), [0, 1, 5, 2, 6, 3, 4]);
+    assert_consistent(&map);
+}
+
+#[test]
+fn insert_before_and_after_existing_key_updates_in_place() {
+    let mut map: IndexMap<i32, i32> = [(1, 10), (2, 20), (3, 30)].into();
+    assert_eq!(map.insert_before(&1, 3, 31), Ok(Some(30)));
+    assert_eq!(map.insert_after(&3, 1, 11), Ok(Some(10)));
+    assert_eq!(keys(&map), [1, 2, 3]);
+    assert_eq!(map[&1], 11);
+    assert_eq!(map[&3], 31);
+    assert_consistent(&map);
+}
+
+#[test]
+fn insert_before_and_after_missing_pivot() {
+    let mut map: IndexMap<i32, i32> = [(1, 10)].into();
+    assert_eq!(map.insert_before(&9, 2, 20), Err(NoSuchPivot));
+    assert_eq!(map.insert_after(&9, 2, 20), Err(NoSuchPivot));
+    assert_eq!(keys(&map), [1]);
+}
+
+/// A key that counts how often it has been compared to other keys.
+#[derive(Debug, Clone)]
+struct CountingKey<'a> {
+    key: i32,
+    comparisons: &'a Cell<usize>,
+}
+
+impl PartialEq for CountingKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CountingKey<'_> {}
+
+impl PartialOrd for CountingKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CountingKey<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparisons.set(self.comparisons.get() + 1);
+        self.key.cmp(&other.key)
+    }
+}
+
+#[test]
+fn from_iter_sorted_input_takes_fast_path() {
+    const LEN: i32 = 1000;
+    let sorted_comparisons = Cell::new(0);
+    let unsorted_comparisons = Cell::new(0);
+    let sorted: IndexMap<CountingKey, i32> = (0..LEN)
+        .map(|key| {
+            let comparisons = &sorted_comparisons;
+            (CountingKey { key, comparisons }, key)
+        })
+        .collect();
+    // Visits all keys in `0..LEN` in a scrambled order since `7919` and `LEN` are coprime.
+    let unsorted: IndexMap<CountingKey, i32> = (0..LEN)
+        .map(|key| key * 7919 % LEN)
+        .map(|key| {
+            let comparisons = &unsorted_comparisons;
+            (CountingKey { key, comparisons }, key)
+        })
+        .collect();
+    assert_eq!(sorted.len(), LEN as usize);
+    assert_eq!(unsorted.len(), LEN as usize);
+    assert!(sorted_comparisons.get() <= 3 * LEN as usize);
+    assert!(sorted_comparisons.get() * 2 < unsorted_comparisons.get());
+    assert_consistent(&sorted);
+    assert_consistent(&unsorted);
+}
+
+#[test]
+fn extend_sorted_hint_with_small_batches_inserts_keys_one_by_one() {
+    const LEN: i32 = 1000;
+    let comparisons = Cell::new(0);
+    let key = |key| CountingKey {
+        key,
+        comparisons: &comparisons,
+    };
+    let mut map = IndexMap::with_capacity_and_sorted_hint(0);
+    map.extend((0..LEN).map(|k| (key(k), k)));
+    comparisons.set(0);
+    for k in LEN..2 * LEN {
+        map.extend([(key(k), k)]);
+    }
+    // Rebuilding the key index on every call would take more than `LEN` comparisons each.
+    assert!(comparisons.get() <= 30 * LEN as usize);
+    assert_eq!(map.len(), 2 * LEN as usize);
+    assert!(map.keys().map(|key| key.key).eq(0..2 * LEN));
+    assert_consistent(&map);
+}
+
+#[test]
+fn extend_sorted_hint_falls_back_on_out_of_order_key() {
+    let mut map = IndexMap::with_capacity_and_sorted_hint(8);
+    map.extend([(1, 'a'), (3, 'b'), (2, 'c'), (4, 'd')]);
+    assert_eq!(keys(&map), [1, 3, 2, 4]);
+    assert_eq!(map[&2], 'c');
+    assert_consistent(&map);
+}
+
+#[test]
+fn extend_sorted_hint_falls_back_on_duplicate_key() {
+    let map: IndexMap<i32, char> = [(1, 'a'), (2, 'b'), (2, 'c'), (3, 'd'), (1, 'e')]
+        .into_iter()
+        .collect();
+    assert_eq!(keys(&map), [1, 2, 3]);
+    assert_eq!(map[&1], 'e');
+    assert_eq!(map[&2], 'c');
+    assert_consistent(&map);
+}
+
+#[test]
+fn extend_sorted_hint_falls_back_on_key_below_existing_max() {
+    let mut map = IndexMap::with_capacity_and_sorted_hint(8);
+    map.extend([(1, 'a'), (5, 'b')]);
+    map.extend([(5, 'c'), (6, 'd')]);
+    map.extend([(2, 'e'), (7, 'f')]);
+    assert_eq!(keys(&map), [1, 5, 6, 2, 7]);
+    assert_eq!(map[&5], 'c');
+    assert_consistent(&map);
+}
+
+/// A key whose ordering only considers its number and ignores its tag.
+#[derive(Debug, Clone)]
+struct Tagged(i32, &'static str);
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[test]
+fn replace_full_vacant() {
+    let mut map = IndexMap::new();
+    assert_eq!(map.replace_full(Tagged(1, "a"), 10), (0, None));
+    assert_eq!(map.replace_full(Tagged(2, "a"), 20), (1, None));
+    assert_eq!(map.len(), 2);
+    assert_consistent(&map);
+}
+
+#[test]
+fn replace_full_occupied() {
+    let mut map: IndexMap<Tagged, i32> = [(Tagged(1, "a"), 10), (Tagged(2, "a"), 20)].into();
+    let (index, replaced) = map.replace_full(Tagged(1, "b"), 11);
+    assert_eq!(index, 0);
+    let (old_key, old_value) = replaced.unwrap();
+    assert_eq!((old_key.1, old_value), ("a", 10));
+    let (key, value) = map.get_index(0).unwrap();
+    assert_eq!((key.1, *value), ("b", 11));
+    assert_eq!(map.get_key_value(&Tagged(1, "z")).unwrap().0 .1, "b");
+    assert_eq!(
+        map.key2slot.get_key_value(&Tagged(1, "z")).unwrap().0 .1,
+        "b"
+    );
+    assert_eq!(map.len(), 2);
+    assert_consistent(&map);
+}
+
+#[test]
+fn iter_while_stops_at_first_failing_entry() {
+    let map: IndexMap<i32, char> = [(1, 'a'), (2, 'b'), (0, 'c'), (3, 'd')].into();
+    let mut calls = 0;
+    let taken: Vec<_> = map
+        .iter_while(|key, _value| {
+            calls += 1;
+            *key > 0
+        })
+        .collect();
+    assert_eq!(taken, [(&1, &'a'), (&2, &'b')]);
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn position_and_rposition() {
+    let map: IndexMap<i32, i32> = [(1, 10), (2, 20), (3, 10), (4, 30)].into();
+    assert_eq!(map.position(|_key, value| *value == 10), Some(0));
+    assert_eq!(map.rposition(|_key, value| *value == 10), Some(2));
+    assert_eq!(map.position(|key, _value| *key > 1), Some(1));
+    assert_eq!(map.rposition(|key, _value| *key > 1), Some(3));
+    assert_eq!(map.position(|_key, value| *value == 0), None);
+    assert_eq!(map.rposition(|_key, value| *value == 0), None);
+}
+
+#[test]
+fn extend_from_slice_reserves_up_front() {
+    let pairs: Vec<(u32, u32)> = (0..100).map(|i| (i, i)).collect();
+    let mut map = IndexMap::new();
+    map.extend_from_slice(&pairs);
+    assert_eq!(map.len(), 100);
+    assert!(map.capacity() >= pairs.len());
+    assert_consistent(&map);
+}
+
+#[test]
+fn extend_from_slice_reserves_for_sorted_hint_map() {
+    let mut map = IndexMap::with_capacity_and_sorted_hint(0);
+    map.extend([(0, 0), (1, 1)]);
+    map.shrink_to_fit();
+    let pairs: Vec<(u32, u32)> = (2..50).map(|i| (i, i)).collect();
+    map.extend_from_slice(&pairs);
+    assert_eq!(map.len(), 50);
+    assert!(map.capacity() >= 50);
+    assert!(map.keys().copied().eq(0..50));
+    assert_consistent(&map);
+}
+
+#[test]
+fn extend_from_slice_last_duplicate_wins() {
+    let mut map: IndexMap<u32, u32> = [(2, 0)].into();
+    map.extend_from_slice(&[(3, 1), (1, 1), (3, 2), (2, 5), (1, 3)]);
+    assert_eq!(keys(&map), [2, 3, 1]);
+    assert_eq!(map[&1], 3);
+    assert_eq!(map[&2], 5);
+    assert_eq!(map[&3], 2);
+    assert_consistent(&map);
+}
+
+#[test]
+fn get_index_range() {
+    let map: IndexMap<i32, i32> = [(5, 0), (4, 1), (3, 2), (2, 3)].into();
+    assert_eq!(map.get_index_range(1..3).unwrap(), [(&4, &1), (&3, &2)]);
+    assert_eq!(
+        map.get_index_range(1..=3).unwrap(),
+        [(&4, &1), (&3, &2), (&2, &3)]
+    );
+    assert_eq!(map.get_index_range(..).unwrap().len(), 4);
+    assert_eq!(map.get_index_range(4..).unwrap().len(), 0);
+    assert!(map.get_index_range(2..5).is_none());
+    assert!(map.get_index_range(2..=4).is_none());
+    assert!(map.get_index_range(5..).is_none());
+}
+
+#[test]
+fn swap_remove_full() {
+    let insert = [0, 4, 2, 12, 8, 7, 11, 5, 3, 17, 19, 22, 23];
+    let mut map = IndexMap::new();
+    for &key in &insert {
+        map.insert(key, key);
+    }
+    assert!(insert.iter().eq(map.keys()));
+    let remove_fail = [99, 77];
+    let remove = [4, 12, 8, 7];
+    for key in &remove_fail {
+        assert!(map.swap_remove_full(key).is_none());
+    }
+    for &key in &remove {
+        let index = map.get_full(&key).unwrap().0;
+        assert_eq!(map.swap_remove_full(&key), Some((index, key, key)));
+    }
+    for key in &insert {
+        assert_eq!(map.get(key).is_some(), !remove.contains(key));
+    }
+    assert_eq!(map.len(), insert.len() - remove.len());
+    assert_eq!(map.keys().count(), insert.len() - remove.len());
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_to_empty() {
+    let mut map: IndexMap<i32, i32> = [(0, 0), (4, 4), (5, 5)].into();
+    assert_eq!(map.swap_remove(&5), Some(5));
+    assert_eq!(map.swap_remove(&4), Some(4));
+    assert_eq!(map.swap_remove(&0), Some(0));
+    assert_eq!(map.swap_remove(&0), None);
+    assert!(map.is_empty());
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_matches_vec_swap_remove() {
+    let insert = [0, 4, 2, 12, 8, 7, 11, 5, 3, 17, 19, 22, 23];
+    let mut map = IndexMap::new();
+    for &key in &insert {
+        map.insert(key, key * 2);
+    }
+    let mut vector = insert.to_vec();
+    for index in [3, 3, 10, 4, 5, 4, 3, 0, 1] {
+        let key = vector.swap_remove(index);
+        assert_eq!(map.swap_remove_entry(&key), Some((key, key * 2)));
+        assert_consistent(&map);
+    }
+    assert!(vector.iter().eq(map.keys()));
+}
+
+#[test]
+fn shift_remove_matches_vec_remove() {
+    let insert = [0, 4, 2, 12, 8, 7, 11, 5, 3, 17, 19, 22, 23];
+    let mut map = IndexMap::new();
+    for &key in &insert {
+        map.insert(key, key * 2);
+    }
+    let mut vector = insert.to_vec();
+    for index in [3, 3, 10, 4, 5, 4, 3, 0, 1] {
+        let key = vector.remove(index);
+        assert_eq!(map.shift_remove_full(&key), Some((index, key, key * 2)));
+        assert_consistent(&map);
+    }
+    assert!(vector.iter().eq(map.keys()));
+    assert_eq!(map.shift_remove(&99), None);
+    assert_eq!(map.shift_remove_entry(&99), None);
+}
+
+#[test]
+fn fold_matches_iterator_fold() {
+    let map: IndexMap<i32, i32> = [(1, 10), (2, 20), (3, 30)].into();
+    let folded = map.fold(0, |acc, key, value| acc * 2 + key * value);
+    let expected = map
+        .iter()
+        .fold(0, |acc, (key, value)| acc * 2 + key * value);
+    assert_eq!(folded, expected);
+}
+
+#[test]
+fn try_fold_matches_iterator_try_fold() {
+    let map: IndexMap<i32, i32> = [(1, 10), (2, 20), (3, 30)].into();
+    let folded = map.try_fold(0, |acc, key, value| Ok::<_, ()>(acc + key * value));
+    let expected = map
+        .iter()
+        .try_fold(0, |acc, (key, value)| Ok::<_, ()>(acc + key * value));
+    assert_eq!(folded, expected);
+    assert_eq!(folded, Ok(140));
+}
+
+#[test]
+fn try_fold_short_circuits_on_error() {
+    let map: IndexMap<i32, i32> = [(1, 10), (2, 20), (3, 30)].into();
+    let mut calls = 0;
+    let folded = map.try_fold(0, |acc, key, value| {
+        calls += 1;
+        if *key == 2 {
+            return Err(*value);
+        }
+        Ok(acc + value)
+    });
+    assert_eq!(folded, Err(20));
+    assert_eq!(calls, 2);
+}
+
+#[test]
+fn try_fold_short_circuits_on_none() {
+    let map: IndexMap<i32, u8> = [(1, 100), (2, 100), (3, 100)].into();
+    let checked_sum = |map: &IndexMap<i32, u8>| {
+        map.try_fold(0_u8, |acc, _key, value| acc.checked_add(*value).ok_or(()))
+            .ok()
+    };
+    assert_eq!(checked_sum(&map), None);
+    assert_eq!(checked_sum(&[(1, 100), (2, 100)].into()), Some(200));
+}
+
+#[test]
+fn swap_remove_entry_index() {
+    let mut map: IndexMap<i32, char> = [(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')].into();
+    assert_eq!(map.swap_remove_entry_index(1), Some((2, 'b')));
+    assert_eq!(keys(&map), [1, 4, 3]);
+    assert_eq!(map.get_index_of(&4), Some(1));
+    assert_consistent(&map);
+    assert_eq!(map.swap_remove_entry_index(2), Some((3, 'c')));
+    assert_eq!(keys(&map), [1, 4]);
+    assert_eq!(map.swap_remove_entry_index(2), None);
+    assert_consistent(&map);
+}
+
+#[test]
+fn and_modify_or_remove_removes_at_zero() {
+    let mut map: IndexMap<char, u32> = [('a', 2), ('b', 1), ('c', 1)].into();
+    let decrement = |count: &mut u32| {
+        *count -= 1;
+        *count == 0
+    };
+    assert!(map.entry('a').and_modify_or_remove(decrement).is_some());
+    assert_eq!(map.get(&'a'), Some(&1));
+    assert!(map.entry('a').and_modify_or_remove(decrement).is_none());
+    assert_eq!(map.get(&'a'), None);
+    assert_eq!(keys(&map), ['c', 'b']);
+    assert_consistent(&map);
+}
+
+#[test]
+fn and_modify_or_remove_keeps_vacant_entry() {
+    let mut map: IndexMap<char, u32> = [('a', 1)].into();
+    let entry = map.entry('b').and_modify_or_remove(|_count| true).unwrap();
+    assert!(matches!(entry, Entry::Vacant(_)));
+    assert_eq!(*entry.or_insert(5), 5);
+    assert_eq!(keys(&map), ['a', 'b']);
+    assert_consistent(&map);
+}
+
+#[test]
+fn retain_sorted_keeps_sorted_map_sorted() {
+    let mut map: IndexMap<i32, i32> = (0..10).map(|key| (key, key * 10)).collect();
+    let mut visited = Vec::new();
+    map.retain_sorted(|key, value| {
+        visited.push(*key);
+        *value += 1;
+        key % 3 != 0
+    });
+    assert_eq!(visited, (0..10).collect::<Vec<_>>());
+    assert_eq!(keys(&map), [1, 2, 4, 5, 7, 8]);
+    assert_eq!(map[&4], 41);
+    assert_eq!(map.get_index_of(&4), Some(2));
+    assert_consistent(&map);
+}
+
+#[test]
+fn retain_sorted_sorts_unsorted_map() {
+    let mut map: IndexMap<i32, i32> = [(3, 0), (1, 0), (4, 0), (2, 0)].into();
+    map.retain_sorted(|key, _value| *key != 4);
+    assert_eq!(keys(&map), [1, 2, 3]);
+    assert_consistent(&map);
+}
+
+#[test]
+fn get_or_insert_default_full() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    let (index, value) = map.get_or_insert_default_full('b');
+    assert_eq!((index, *value), (1, 2));
+    *value += 1;
+    let (index, value) = map.get_or_insert_default_full('c');
+    assert_eq!((index, *value), (2, 0));
+    *value += 5;
+    assert_eq!(map[&'b'], 3);
+    assert_eq!(map[&'c'], 5);
+    assert_eq!(map.get_index_of(&'c'), Some(2));
+    assert_consistent(&map);
+}
+
+#[test]
+fn zip_values_mut_with_fewer_values() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3)].into();
+    map.zip_values_mut([10, 20]);
+    assert!(map.iter().eq([(&'a', &10), (&'b', &20), (&'c', &3)]));
+}
+
+#[test]
+fn zip_values_mut_with_more_values() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    map.zip_values_mut([10, 20, 30, 40]);
+    assert!(map.iter().eq([(&'a', &10), (&'b', &20)]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn try_into_pairs_array_exact_length() {
+    let map: IndexMap<char, u32> = [('b', 2), ('a', 1), ('c', 3)].into();
+    assert_eq!(
+        map.try_into_pairs_array::<3>(),
+        Ok([('b', 2), ('a', 1), ('c', 3)])
+    );
+}
+
+#[test]
+fn try_into_pairs_array_length_mismatch() {
+    let map: IndexMap<char, u32> = [('b', 2), ('a', 1), ('c', 3)].into();
+    let map = map.try_into_pairs_array::<2>().unwrap_err();
+    let map = map.try_into_pairs_array::<4>().unwrap_err();
+    assert_eq!(keys(&map), ['b', 'a', 'c']);
+    assert_consistent(&map);
+}
+
+#[test]
+fn shrink_index_if_sparse_compacts_reduced_map() {
+    let mut map: IndexMap<u32, u32> = (0..100).map(|key| (key, key)).collect();
+    map.retain(|key, _value| key % 20 == 0);
+    assert!(map.capacity() >= 100);
+    map.shrink_index_if_sparse();
+    assert!(map.capacity() < 100);
+    assert_eq!(keys(&map), [0, 20, 40, 60, 80]);
+    assert_consistent(&map);
+}
+
+#[test]
+fn shrink_index_if_sparse_ignores_dense_map() {
+    let mut map: IndexMap<u32, u32> = IndexMap::with_capacity(100);
+    map.extend((0..50).map(|key| (key, key)));
+    let capacity = map.capacity();
+    map.shrink_index_if_sparse();
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.len(), 50);
+    assert_consistent(&map);
+}
+
+#[test]
+fn indices_where() {
+    let map: IndexMap<i32, i32> = [(5, 1), (2, 2), (8, 3), (1, 4), (4, 5)].into();
+    let indices: Vec<usize> = map.indices_where(|key, _value| key % 2 == 0).collect();
+    assert_eq!(indices, [1, 2, 4]);
+    for index in indices {
+        assert_eq!(map.get_index(index).unwrap().0 % 2, 0);
+    }
+    assert_eq!(map.indices_where(|_key, value| *value > 9).count(), 0);
+}
+
+#[test]
+fn swap_remove_range() {
+    let mut map: IndexMap<i32, i32> = (0..8).map(|key| (key, key * 10)).collect();
+    let removed = map.swap_remove_range(1..3);
+    assert_eq!(removed, [(1, 10), (2, 20)]);
+    assert_eq!(keys(&map), [0, 6, 7, 3, 4, 5]);
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_range_with_short_tail() {
+    let mut map: IndexMap<i32, i32> = (0..6).map(|key| (key, key * 10)).collect();
+    let removed = map.swap_remove_range(1..=4);
+    assert_eq!(removed, [(1, 10), (2, 20), (3, 30), (4, 40)]);
+    assert_eq!(keys(&map), [0, 5]);
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_range_exhaustive() {
+    for len in 0..=8 {
+        for start in 0..=len {
+            for end in start..=len {
+                let mut map: IndexMap<i32, i32> = (0..len).map(|key| (key, key * 10)).collect();
+                let removed = map.swap_remove_range(start as usize..end as usize);
+                let expected: Vec<_> = (start..end).map(|key| (key, key * 10)).collect();
+                assert_eq!(removed, expected);
+                assert_eq!(map.len(), (len - (end - start)) as usize);
+                for key in 0..len {
+                    assert_eq!(map.contains_key(&key), !(start..end).contains(&key));
+                }
+                assert_consistent(&map);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn swap_remove_range_out_of_bounds() {
+    let mut map: IndexMap<i32, i32> = (0..4).map(|key| (key, key)).collect();
+    map.swap_remove_range(2..5);
+}
+
+#[test]
+fn sorted_iter_yields_ascending_keys() {
+    let map: IndexMap<i32, char> = [(3, 'a'), (1, 'b'), (2, 'c')].into();
+    assert!(map.sorted_iter().eq([(&1, &'b'), (&2, &'c'), (&3, &'a')]));
+    assert!(map
+        .sorted_iter()
+        .rev()
+        .eq([(&3, &'a'), (&2, &'c'), (&1, &'b')]));
+    assert_eq!(map.sorted_iter().len(), 3);
+}
+
+#[test]
+fn group_runs_sorted_counts_buckets() {
+    let map: IndexMap<i32, ()> = [35, 1, 12, 5, 31, 17, 3, 39]
+        .into_iter()
+        .map(|key| (key, ()))
+        .collect();
+    let runs = map.group_runs_sorted(|key| key / 10);
+    assert_eq!(runs, [(0, 3), (1, 2), (3, 3)]);
+    assert!(IndexMap::<i32, ()>::new()
+        .group_runs_sorted(|key| key / 10)
+        .is_empty());
+}
+
+#[test]
+fn get_full_mut() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3)].into();
+    let (index, key, value) = map.get_full_mut(&'b').unwrap();
+    assert_eq!((index, *key), (1, 'b'));
+    *value = 20;
+    assert_eq!(map.get_index_of(&'b'), Some(index));
+    assert_eq!(map[&'b'], 20);
+    assert!(map.get_full_mut(&'z').is_none());
+}
+
+#[test]
+fn entry_all_sees_vacant_and_occupied_entries() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('c', 3)].into();
+    let mut seen = Vec::new();
+    map.entry_all(['a', 'b', 'c', 'd', 'b'], |key, entry| match entry {
+        Entry::Vacant(entry) => {
+            seen.push((*key, false));
+            entry.insert(0);
+        }
+        Entry::Occupied(mut entry) => {
+            seen.push((*key, true));
+            *entry.get_mut() += 10;
+        }
+    });
+    assert_eq!(
+        seen,
+        [
+            ('a', true),
+            ('b', false),
+            ('c', true),
+            ('d', false),
+            ('b', true)
+        ]
+    );
+    assert!(map
+        .iter()
+        .eq([(&'a', &11), (&'c', &13), (&'b', &10), (&'d', &0)]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn to_vec_sorted_by_leaves_map_untouched() {
+    let map: IndexMap<char, u32> = [('a', 3), ('b', 1), ('c', 2)].into();
+    let sorted = map.to_vec_sorted_by(|lhs, rhs| lhs.1.cmp(rhs.1));
+    assert_eq!(sorted, [(&'b', &1), (&'c', &2), (&'a', &3)]);
+    let sorted = map.to_vec_sorted_by(|lhs, rhs| rhs.0.cmp(lhs.0));
+    assert_eq!(sorted, [(&'c', &2), (&'b', &1), (&'a', &3)]);
+    assert_eq!(keys(&map), ['a', 'b', 'c']);
+}
+
+#[test]
+fn is_disjoint_keys() {
+    let map: IndexMap<i32, char> = [(1, 'a'), (2, 'b'), (3, 'c')].into();
+    let disjoint: IndexMap<i32, u8> = [(4, 0), (5, 0)].into();
+    let overlapping: IndexMap<i32, u8> = [(5, 0), (6, 0), (7, 0), (3, 0)].into();
+    assert!(map.is_disjoint_keys(&disjoint));
+    assert!(disjoint.is_disjoint_keys(&map));
+    assert!(!map.is_disjoint_keys(&overlapping));
+    assert!(!overlapping.is_disjoint_keys(&map));
+    assert!(map.is_disjoint_keys(&IndexMap::<i32, u8>::new()));
+}
+
+#[test]
+fn common_keys_yields_both_values() {
+    let lhs: IndexMap<char, u32> = [('d', 4), ('a', 1), ('b', 2), ('c', 3)].into();
+    let rhs: IndexMap<char, &str> = [('c', "three"), ('e', "five"), ('d', "four")].into();
+    let common: Vec<_> = lhs.common_keys(&rhs).collect();
+    assert_eq!(common, [(&'d', &4, &"four"), (&'c', &3, &"three")]);
+    assert_eq!(lhs.common_keys(&IndexMap::<char, ()>::new()).count(), 0);
+}
+
+#[test]
+fn retain_keys_in() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4)].into();
+    let allowed: IndexSet<char> = ['d', 'x', 'b'].into();
+    map.retain_keys_in(&allowed);
+    assert!(map.iter().eq([(&'b', &2), (&'d', &4)]));
+    assert_eq!(map.get_index_of(&'d'), Some(1));
+    assert_consistent(&map);
+}
+
+#[test]
+fn remove_all_keys_ignores_absent_keys() {
+    let mut map: IndexMap<i32, i32> = (0..8).map(|key| (key, key * 10)).collect();
+    map.remove_all_keys([6, 42, 1, 3, 1, -1]);
+    assert_eq!(keys(&map), [0, 2, 4, 5, 7]);
+    for (index, key) in [0, 2, 4, 5, 7].into_iter().enumerate() {
+        assert_eq!(map.get_index_of(&key), Some(index));
+        assert_eq!(map[&key], key * 10);
+    }
+    assert_consistent(&map);
+    map.remove_all_keys([]);
+    assert_eq!(map.len(), 5);
+}
+
+#[test]
+fn diff_reports_removed_added_and_changed() {
+    let old: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4)].into();
+    let new: IndexMap<char, u32> = [('e', 5), ('c', 30), ('a', 1), ('f', 6), ('b', 20)].into();
+    let diff = old.diff(&new);
+    assert!(diff.removed().eq([(&'d', &4)]));
+    assert!(diff.added().eq([(&'e', &5), (&'f', &6)]));
+    assert!(diff.changed().eq([(&'b', &2, &20), (&'c', &3, &30)]));
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn diff_without_changes_is_empty() {
+    let old: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    let new: IndexMap<char, u32> = [('b', 2), ('a', 1)].into();
+    let diff = old.diff(&new);
+    assert_eq!(diff.removed().count(), 0);
+    assert_eq!(diff.added().count(), 0);
+    assert_eq!(diff.changed().count(), 0);
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn apply_patch_transforms_old_into_new() {
+    let old: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4)].into();
+    let new: IndexMap<char, u32> = [('e', 5), ('c', 30), ('a', 1), ('f', 6), ('b', 20)].into();
+    let patch = old.diff(&new).to_patch();
+    let mut map = old.clone();
+    map.apply_patch(&patch);
+    assert_eq!(map.len(), new.len());
+    assert!(map.sorted_iter().eq(new.sorted_iter()));
+    assert!(map.diff(&new).is_empty());
+    assert_eq!(keys(&map), ['a', 'b', 'c', 'e', 'f']);
+    assert_consistent(&map);
+}
+
+#[test]
+fn apply_empty_patch_is_a_no_op() {
+    let old: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    let patch = old.diff(&old).to_patch();
+    let mut map = old.clone();
+    map.apply_patch(&patch);
+    assert_eq!(map, old);
+}
+
+#[test]
+fn swap_remove_reporting_middle_reports_moved_key() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4)].into();
+    assert_eq!(map.swap_remove_reporting(&'b'), Some((2, Some((1, &'d')))));
+    assert_eq!(map.get_index_of(&'d'), Some(1));
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_reporting_last_reports_nothing() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    assert_eq!(map.swap_remove_reporting(&'b'), Some((2, None)));
+    assert_eq!(map.swap_remove_reporting(&'z'), None);
+    assert_eq!(map.swap_remove_reporting(&'a'), Some((1, None)));
+    assert!(map.is_empty());
+    assert_consistent(&map);
+}
+
+#[test]
+fn count_keys_below() {
+    let map: IndexMap<i32, ()> = [40, 10, 30, 20].into_iter().map(|key| (key, ())).collect();
+    for (bound, expected) in [(0, 0), (10, 0), (11, 1), (25, 2), (30, 2), (40, 3), (41, 4)] {
+        assert_eq!(map.count_keys_below(&bound), expected, "bound = {bound}");
+    }
+    assert_eq!(IndexMap::<i32, ()>::new().count_keys_below(&5), 0);
+}
+
+#[test]
+fn get_index_checked() {
+    let map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    assert_eq!(map.get_index_checked(1), Ok((&'b', &2)));
+    let error = map.get_index_checked(5).unwrap_err();
+    assert_eq!(error, IndexOutOfBounds { index: 5, len: 2 });
+    assert_eq!((error.index, error.len), (5, 2));
+    assert_eq!(
+        map.get_index_checked(2),
+        Err(IndexOutOfBounds { index: 2, len: 2 })
+    );
+}
+
+#[test]
+fn reserve_for_keys_avoids_reallocation() {
+    let mut map: IndexMap<i32, i32> = (0..4).map(|key| (key, key)).collect();
+    map.shrink_to_fit();
+    let keys: IndexSet<i32> = (2..40).collect();
+    map.reserve_for_keys(&keys);
+    assert!(map.capacity() >= 40);
+    let capacity = map.capacity();
+    let before = map.slots.as_ptr();
+    for &key in &keys {
+        map.insert(key, key);
+    }
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.slots.as_ptr(), before);
+    assert_eq!(map.len(), 40);
+    assert_consistent(&map);
+}
+
+#[test]
+fn into_iter_sorted_yields_ascending_keys() {
+    let map: IndexMap<i32, char> = [(3, 'c'), (1, 'a'), (4, 'd'), (2, 'b')].into();
+    let pairs: Vec<(i32, char)> = map.into_iter_sorted().collect();
+    assert_eq!(pairs, [(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')]);
+    assert_eq!(IndexMap::<i32, char>::new().into_iter_sorted().count(), 0);
+}
+
+#[test]
+fn append_new_keeps_existing_entries() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    let mut other: IndexMap<char, u32> = [('d', 40), ('b', 20), ('c', 30), ('a', 10)].into();
+    map.append_new(&mut other);
+    assert!(map
+        .iter()
+        .eq([(&'a', &1), (&'b', &2), (&'d', &40), (&'c', &30)]));
+    assert!(other.iter().eq([(&'b', &20), (&'a', &10)]));
+    assert_consistent(&map);
+    assert_consistent(&other);
+}
+
+#[test]
+fn enumerate_vec() {
+    let map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    let entries = map.enumerate_vec();
+    assert_eq!(entries.len(), map.len());
+    for (position, ((index, key, value), pair)) in entries.iter().zip(map.iter()).enumerate() {
+        assert_eq!(*index, position);
+        assert_eq!((*key, *value), pair);
+    }
+}
+
+#[test]
+fn swap_value() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    assert_eq!(map.swap_value(&'b', 20), Some(2));
+    assert_eq!(map[&'b'], 20);
+    assert_eq!(map.swap_value(&'c', 30), None);
+    assert!(!map.contains_key(&'c'));
+    assert_eq!(map.len(), 2);
+    assert_consistent(&map);
+}
+
+#[test]
+fn retain_split() {
+    let mut map: IndexMap<i32, char> = [(5, 'a'), (2, 'b'), (8, 'c'), (1, 'd'), (4, 'e')].into();
+    let removed = map.retain_split(|key, _value| key % 2 == 0);
+    assert!(map.iter().eq([(&2, &'b'), (&8, &'c'), (&4, &'e')]));
+    assert!(removed.iter().eq([(&5, &'a'), (&1, &'d')]));
+    assert_consistent(&map);
+    assert_consistent(&removed);
+}
+
+#[test]
+fn dedup_values_keeps_first_occurrences() {
+    let mut map: IndexMap<char, u32> =
+        [('a', 1), ('b', 2), ('c', 1), ('d', 3), ('e', 2), ('f', 1)].into();
+    assert_eq!(map.dedup_values(), 3);
+    assert!(map.iter().eq([(&'a', &1), (&'b', &2), (&'d', &3)]));
+    assert_consistent(&map);
+    assert_eq!(map.dedup_values(), 0);
+}
+
+#[test]
+fn pairs_mut_and_for_each_value_mut() {
+    let mut map: IndexMap<char, u32> = [('b', 2), ('a', 1)].into();
+    for (key, value) in map.pairs_mut() {
+        if *key == 'a' {
+            *value += 10;
+        }
+    }
+    map.for_each_value_mut(|value| *value *= 2);
+    assert!(map.iter().eq([(&'b', &4), (&'a', &22)]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn try_from_sorted_pairs_accepts_sorted_input() {
+    let map = IndexMap::try_from_sorted_pairs([(1, 'a'), (2, 'b'), (5, 'c')]).unwrap();
+    assert!(map.iter().eq([(&1, &'a'), (&2, &'b'), (&5, &'c')]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn try_from_sorted_pairs_rejects_out_of_order_key() {
+    assert_eq!(
+        IndexMap::try_from_sorted_pairs([(1, 'a'), (3, 'b'), (2, 'c'), (4, 'd')]),
+        Err(UnsortedInput { index: 2 })
+    );
+}
+
+#[test]
+fn try_from_sorted_pairs_rejects_duplicate_key() {
+    assert_eq!(
+        IndexMap::try_from_sorted_pairs([(1, 'a'), (1, 'b')]),
+        Err(UnsortedInput { index: 1 })
+    );
+}
+
+#[test]
+fn filter_map_values() {
+    let map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4)].into();
+    let map = map.filter_map_values(|key, value| (*key != 'b').then(|| value * 10));
+    assert!(map.iter().eq([(&'a', &10), (&'c', &30), (&'d', &40)]));
+    assert_eq!(map.get_index_of(&'d'), Some(2));
+    assert_consistent(&map);
+}
+
+#[test]
+fn intern_returns_stable_indices() {
+    let mut map: IndexMap<&str, u32> = IndexMap::new();
+    assert_eq!(map.intern("a"), 0);
+    assert_eq!(map.intern("b"), 1);
+    assert_eq!(map.intern("a"), 0);
+    assert_eq!(map.intern("c"), 2);
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[&"b"], 0);
+    assert_consistent(&map);
+}
+
+#[test]
+fn retain_first_n() {
+    let map: IndexMap<i32, ()> = [6, 1, 4, 3, 2, 5, 8]
+        .into_iter()
+        .map(|key| (key, ()))
+        .collect();
+    for (n, expected) in [
+        (0, &[][..]),
+        (2, &[6, 4][..]),
+        (4, &[6, 4, 2, 8][..]),
+        (9, &[6, 4, 2, 8][..]),
+    ] {
+        let mut map = map.clone();
+        map.retain_first_n(n, |key, _value| key % 2 == 0);
+        assert_eq!(keys(&map), expected, "n = {n}");
+        assert_consistent(&map);
+    }
+}
+
+#[test]
+fn is_empty() {
+    let mut map = IndexMap::new();
+    assert!(map.is_empty());
+    map.insert('a', 1);
+    assert!(!map.is_empty());
+    map.clear();
+    assert!(map.is_empty());
+}
+
+#[test]
+fn swap_remove_by_value_removes_first_match() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 2), ('d', 3)].into();
+    assert_eq!(
+        map.swap_remove_by_value(|value| *value == 2),
+        Some(('b', 2))
+    );
+    assert!(map.iter().eq([(&'a', &1), (&'d', &3), (&'c', &2)]));
+    assert_eq!(map.swap_remove_by_value(|value| *value > 5), None);
+    assert_eq!(map.len(), 3);
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_moves_last_key_into_hole() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4)].into();
+    assert_eq!(map.swap_remove(&'b'), Some(2));
+    assert_eq!(map.get_index_of(&'d'), Some(1));
+    assert_eq!(map.get_index(1), Some((&'d', &4)));
+    assert_eq!(map.swap_remove(&'b'), None);
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_last_element() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3)].into();
+    assert_eq!(map.swap_remove(&'c'), Some(3));
+    assert_eq!(keys(&map), ['a', 'b']);
+    assert_consistent(&map);
+}
+
+#[test]
+fn swap_remove_single_element() {
+    let mut map: IndexMap<char, u32> = [('a', 1)].into();
+    assert_eq!(map.swap_remove(&'z'), None);
+    assert_eq!(map.swap_remove(&'a'), Some(1));
+    assert!(map.is_empty());
+    assert!(map.key2slot.is_empty());
+}
+
+#[test]
+fn bulk_insert_sorted_merges_overlapping_keys() {
+    let mut map: IndexMap<i32, char> = [(1, 'a'), (3, 'a'), (5, 'a'), (7, 'a')].into();
+    map.bulk_insert_sorted([(0, 'b'), (3, 'b'), (4, 'b'), (7, 'b'), (9, 'b')]);
+    assert!(map.iter().eq([
+        (&0, &'b'),
+        (&1, &'a'),
+        (&3, &'b'),
+        (&4, &'b'),
+        (&5, &'a'),
+        (&7, &'b'),
+        (&9, &'b'),
+    ]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn bulk_insert_sorted_without_pairs_is_a_no_op() {
+    let mut map: IndexMap<i32, char> = [(1, 'a'), (5, 'a')].into();
+    let original = map.clone();
+    let capacity = map.capacity();
+    map.bulk_insert_sorted([]);
+    assert_eq!(map, original);
+    assert_eq!(map.capacity(), capacity);
+    assert_consistent(&map);
+}
+
+#[test]
+fn bulk_insert_sorted_falls_back_on_unsorted_input() {
+    let mut map: IndexMap<i32, char> = [(1, 'a'), (5, 'a')].into();
+    map.bulk_insert_sorted([(4, 'b'), (1, 'b'), (0, 'b')]);
+    assert!(map
+        .iter()
+        .eq([(&1, &'b'), (&5, &'a'), (&4, &'b'), (&0, &'b')]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn shift_remove_middle_preserves_order() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4), ('e', 5)].into();
+    assert_eq!(map.shift_remove(&'b'), Some(2));
+    assert_eq!(keys(&map), ['a', 'c', 'd', 'e']);
+    for (index, key) in ['a', 'c', 'd', 'e'].into_iter().enumerate() {
+        assert_eq!(map.get_index_of(&key), Some(index));
+    }
+    assert_eq!(map.shift_remove(&'b'), None);
+    assert_consistent(&map);
+}
+
+#[test]
+fn shift_remove_first_last_and_single() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3)].into();
+    assert_eq!(map.shift_remove(&'c'), Some(3));
+    assert_eq!(map.shift_remove(&'a'), Some(1));
+    assert_eq!(map.get_index_of(&'b'), Some(0));
+    assert_consistent(&map);
+    assert_eq!(map.shift_remove(&'b'), Some(2));
+    assert!(map.is_empty());
+    assert!(map.key2slot.is_empty());
+}
+
+#[test]
+fn stats() {
+    let mut map: IndexMap<char, u32> = IndexMap::with_capacity(8);
+    assert_eq!(
+        map.stats(),
+        MapStats {
+            len: 0,
+            slots_capacity: map.capacity(),
+            key2slot_len: 0,
+        }
+    );
+    map.extend([('b', 2), ('a', 1), ('c', 3)]);
+    let stats = map.stats();
+    assert_eq!(stats.len, map.len());
+    assert_eq!(stats.key2slot_len, map.len());
+    assert!(stats.slots_capacity >= 8);
+}
+
+#[test]
+fn check_consistency() {
+    let mut map: IndexMap<char, u32> = [('b', 2), ('a', 1), ('c', 3)].into();
+    assert!(map.check_consistency());
+    map.swap_remove(&'a');
+    assert!(map.check_consistency());
+    map.slots.swap(0, 1);
+    assert!(!map.check_consistency());
+    map.slots.pop();
+    assert!(!map.check_consistency());
+}
+
+#[test]
+fn get_index() {
+    let map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    assert_eq!(map.get_index(0), Some((&'c', &3)));
+    assert_eq!(map.get_index(2), Some((&'b', &2)));
+    assert_eq!(map.get_index(3), None);
+    assert_eq!(IndexMap::<char, u32>::new().get_index(0), None);
+}
+
+#[test]
+fn normalize_is_idempotent() {
+    let mut map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    let original = map.clone();
+    map.normalize();
+    assert_eq!(map, original);
+    map.normalize();
+    assert_eq!(map, original);
+    assert_consistent(&map);
+}
+
+#[test]
+fn normalize_recomputes_slot_indices() {
+    let mut map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    map.key2slot.insert('a', SlotIndex::new(7));
+    map.key2slot.insert('b', SlotIndex::new(0));
+    map.normalize();
+    assert_eq!(keys(&map), ['c', 'a', 'b']);
+    assert_consistent(&map);
+}
+
+#[test]
+fn get_index_mut() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    let (key, value) = map.get_index_mut(1).unwrap();
+    assert_eq!(*key, 'b');
+    *value = 20;
+    assert_eq!(map.get(&'b'), Some(&20));
+    assert_eq!(map.get(&'a'), Some(&1));
+    assert!(map.get_index_mut(2).is_none());
+}
+
+#[test]
+fn get_range_mut_mutates_window_only() {
+    let mut map: IndexMap<i32, i32> = (0..6).map(|key| (key, key)).collect();
+    let mut window = map.get_range_mut(2..4).unwrap();
+    assert_eq!(window.len(), 2);
+    assert_eq!(window.get_index(0), Some((&2, &2)));
+    for (_key, value) in window.iter_mut() {
+        *value *= 100;
+    }
+    assert!(map.iter().eq([
+        (&0, &0),
+        (&1, &1),
+        (&2, &200),
+        (&3, &300),
+        (&4, &4),
+        (&5, &5)
+    ]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn get_range_mut_out_of_range() {
+    let mut map: IndexMap<i32, i32> = (0..4).map(|key| (key, key)).collect();
+    assert!(map.get_range_mut(2..5).is_none());
+    assert!(map.get_range_mut(5..).is_none());
+    assert!(map.get_range_mut(4..).unwrap().is_empty());
+    assert_eq!(map.get_range_mut(..).unwrap().len(), 4);
+}
+
+#[test]
+fn get_index_of_tracks_insertion_and_swap_remove() {
+    let mut map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    assert_eq!(map.get_index_of(&'c'), Some(0));
+    assert_eq!(map.get_index_of(&'b'), Some(2));
+    assert_eq!(map.get_index_of(&'z'), None);
+    map.swap_remove(&'c');
+    assert_eq!(map.get_index_of(&'c'), None);
+    assert_eq!(map.get_index_of(&'b'), Some(0));
+    assert_eq!(map.get_index_of(&'a'), Some(1));
+}
+
+#[test]
+fn swap_positions_of() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3)].into();
+    assert!(map.swap_positions_of(&'a', &'c'));
+    assert_eq!(keys(&map), ['c', 'b', 'a']);
+    assert_eq!(map.get_index_of(&'a'), Some(2));
+    assert_eq!(map[&'a'], 1);
+    assert_consistent(&map);
+    assert!(!map.swap_positions_of(&'a', &'z'));
+    assert!(!map.swap_positions_of(&'z', &'a'));
+    assert_eq!(keys(&map), ['c', 'b', 'a']);
+}
+
+#[test]
+fn keys_iterator() {
+    let map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    let mut iter = map.keys();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(&'c'));
+    assert_eq!(iter.len(), 2);
+    assert!(iter.clone().eq([&'a', &'b']));
+    assert!(map.keys().rev().eq([&'b', &'a', &'c']));
+}
+
+#[test]
+fn extract_if_sorted_visits_keys_in_order() {
+    let mut map: IndexMap<i32, i32> = [(5, 0), (2, 0), (8, 0), (1, 0), (4, 0)].into();
+    let mut visited = Vec::new();
+    let extracted: Vec<(i32, i32)> = map
+        .extract_if_sorted(|key, value| {
+            visited.push(*key);
+            *value = *key;
+            visited.len() <= 2
+        })
+        .collect();
+    assert_eq!(visited, [1, 2, 4, 5, 8]);
+    assert_eq!(extracted, [(1, 1), (2, 2)]);
+    assert!(map.iter().eq([(&5, &5), (&8, &8), (&4, &4)]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn iter_and_iter_mut_follow_insertion_order() {
+    let mut map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    assert!(map.iter().eq([(&'c', &3), (&'a', &1), (&'b', &2)]));
+    assert_eq!(
+        map.iter_mut().map(|(key, _value)| *key).collect::<Vec<_>>(),
+        ['c', 'a', 'b']
+    );
+}
+
+#[test]
+fn values_and_values_mut() {
+    let mut map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    assert!(map.values().eq(&[3, 1, 2]));
+    assert_eq!(map.values().len(), 3);
+    assert!(map.values().rev().eq(&[2, 1, 3]));
+    assert_eq!(map.values().sum::<u32>(), 6);
+    for value in map.values_mut() {
+        *value *= 10;
+    }
+    assert!(map.values_mut().rev().map(|value| *value).eq([20, 10, 30]));
+    assert_eq!(map[&'a'], 10);
+}
+
+#[test]
+fn into_iter_owned() {
+    let map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    let mut iter = map.clone().into_iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(('c', 3)));
+    assert_eq!(iter.next_back(), Some(('b', 2)));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next(), Some(('a', 1)));
+    assert_eq!(iter.next(), None);
+    let mut pairs = Vec::new();
+    for (key, value) in map.clone() {
+        pairs.push((key, value));
+    }
+    assert_eq!(pairs, [('c', 3), ('a', 1), ('b', 2)]);
+    assert_eq!(pairs.into_iter().collect::<IndexMap<_, _>>(), map);
+}
+
+#[test]
+fn into_keys_and_into_values() {
+    let map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    assert_eq!(map.clone().into_keys().collect::<Vec<_>>(), keys(&map));
+    assert_eq!(
+        map.clone().into_values().collect::<Vec<_>>(),
+        map.values().copied().collect::<Vec<_>>()
+    );
+    assert!(map.clone().into_keys().rev().eq(['b', 'a', 'c']));
+    assert_eq!(map.clone().into_values().len(), 3);
+    assert_eq!(map.into_values().next_back(), Some(2));
+}
+
+#[test]
+fn iter_rev_sorted_yields_descending_keys() {
+    let map: IndexMap<i32, char> = [(3, 'c'), (1, 'a'), (4, 'd'), (2, 'b')].into();
+    let keys: Vec<i32> = map.iter_rev_sorted().map(|(key, _value)| *key).collect();
+    assert_eq!(keys, [4, 3, 2, 1]);
+    assert!(map
+        .iter_rev_sorted()
+        .all(|(key, value)| map.get(key) == Some(value)));
+}
+
+#[test]
+fn get_mut() {
+    let mut map: IndexMap<char, u32> = [('a', 1), ('b', 2)].into();
+    *map.get_mut(&'a').unwrap() += 10;
+    assert_eq!(map.get(&'a'), Some(&11));
+    assert_eq!(map.get_mut(&'z'), None);
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn or_try_insert_with_inserts_on_success() {
+    let mut map: IndexMap<char, u32> = [('a', 1)].into();
+    let value = map
+        .entry('b')
+        .or_try_insert_with(|| Ok::<_, ()>(2))
+        .unwrap();
+    *value += 1;
+    assert_eq!(map[&'b'], 3);
+    let value = map
+        .entry('a')
+        .or_try_insert_with(|| -> Result<u32, ()> { unreachable!() })
+        .unwrap();
+    assert_eq!(*value, 1);
+    assert_consistent(&map);
+}
+
+#[test]
+fn or_try_insert_with_leaves_no_slot_on_error() {
+    let mut map: IndexMap<char, u32> = [('a', 1)].into();
+    assert_eq!(
+        map.entry('b').or_try_insert_with(|| Err("nope")),
+        Err("nope")
+    );
+    assert_eq!(map.len(), 1);
+    assert!(!map.contains_key(&'b'));
+    assert_consistent(&map);
+}
+
+#[test]
+fn get_key_value_returns_stored_key() {
+    let map: IndexMap<Tagged, u32> = [(Tagged(1, "a"), 1), (Tagged(2, "a"), 2)].into();
+    let (key, value) = map.get_key_value(&Tagged(2, "z")).unwrap();
+    assert_eq!((key.1, *value), ("a", 2));
+    assert_eq!(map.get_key_value(&Tagged(3, "a")), None);
+}
+
+#[test]
+fn insert_keeps_stored_key() {
+    let mut map: IndexMap<Tagged, u32> = [(Tagged(1, "a"), 1), (Tagged(2, "a"), 2)].into();
+    assert_eq!(map.insert_full(Tagged(2, "b"), 20), Some((1, 2)));
+    let (key, value) = map.get_key_value(&Tagged(2, "z")).unwrap();
+    assert_eq!((key.1, *value), ("a", 20));
+    assert_eq!(map.get_index(1).unwrap().0 .1, "a");
+    assert_eq!(
+        map.key2slot.get_key_value(&Tagged(2, "z")).unwrap().0 .1,
+        "a"
+    );
+    assert_consistent(&map);
+}
+
+#[test]
+fn collect_into_chains() {
+    let mut map = IndexMap::new();
+    map.collect_into([('a', 1), ('b', 2)])
+        .collect_into([('c', 3), ('a', 10)])
+        .collect_into(core::iter::empty());
+    assert!(map.iter().eq([(&'a', &10), (&'b', &2), (&'c', &3)]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn get_full_and_get_full_mut() {
+    let mut map: IndexMap<char, u32> = [('c', 3), ('a', 1), ('b', 2)].into();
+    assert_eq!(map.get_full(&'a'), Some((1, &'a', &1)));
+    assert_eq!(map.get_full(&'z'), None);
+    let (index, _key, value) = map.get_full_mut(&'b').unwrap();
+    *value = 20;
+    assert_eq!(map.get_index_of(&'b'), Some(index));
+    assert!(map.get_full_mut(&'z').is_none());
+    map.swap_remove(&'c');
+    assert_eq!(map.get_full(&'b'), Some((0, &'b', &20)));
+    assert_eq!(
+        map.get_full(&'b').unwrap().0,
+        map.get_index_of(&'b').unwrap()
+    );
+}
+
+#[test]
+fn index_and_key_of_value() {
+    let map: IndexMap<char, u32> = [('c', 3), ('a', 2), ('b', 2)].into();
+    assert_eq!(map.index_of_value(|value| *value == 2), Some(1));
+    assert_eq!(map.key_of_value(|value| *value == 2), Some(&'a'));
+    assert_eq!(map.index_of_value(|value| *value > 5), None);
+    assert_eq!(map.key_of_value(|value| *value > 5), None);
+}
+
+#[test]
+fn with_capacity_and_reserve() {
+    let mut map: IndexMap<i32, i32> = IndexMap::with_capacity(10);
+    assert!(map.capacity() >= 10);
+    assert!(map.is_empty());
+    let capacity = map.capacity();
+    map.extend((0..10).map(|key| (key, key)));
+    assert_eq!(map.capacity(), capacity);
+    map.reserve(20);
+    assert!(map.capacity() >= 30);
+    assert_eq!(map.len(), 10);
+}
+
+#[test]
+fn try_insert() {
+    let mut map = IndexMap::new();
+    assert_eq!(map.try_insert('a', 1), Ok(None));
+    assert_eq!(map.try_insert('b', 2), Ok(None));
+    assert_eq!(map.try_insert('a', 10), Ok(Some(1)));
+    assert!(map.iter().eq([(&'a', &10), (&'b', &2)]));
+    assert_consistent(&map);
+}
+
+#[test]
+fn retain_preserves_order_and_indices() {
+    let mut map: IndexMap<i32, i32> = [(5, 0), (2, 0), (8, 0), (1, 0), (4, 0)].into();
+    map.retain(|key, value| {
+        *value = key * 10;
+        key % 2 == 0
+    });
+    assert_eq!(keys(&map), [2, 8, 4]);
+    for (index, key) in [2, 8, 4].into_iter().enumerate() {
+        assert_eq!(map.get(&key), Some(&(key * 10)));
+        assert_eq!(map.get_index_of(&key), Some(index));
+    }
+    assert_eq!(map.get(&5), None);
+    assert_consistent(&map);
+}
+
+#[test]
+fn sort_keys() {
+    let mut map: IndexMap<i32, char> = [(3, 'c'), (1, 'a'), (4, 'd'), (2, 'b')].into();
+    map.sort_keys();
+    assert!(map
+        .iter()
+        .eq([(&1, &'a'), (&2, &'b'), (&3, &'c'), (&4, &'d')]));
+    for key in 1..=4 {
+        assert_eq!(map.get_index_of(&key), Some(key as usize - 1));
+    }
+    assert_consistent(&map);
+}
+
+#[test]
+fn sort_by_value_descending() {
+    let mut map: IndexMap<char, u32> = [('a', 2), ('b', 3), ('c', 1), ('d', 3)].into();
+    map.sort_by(|_k1, v1, _k2, v2| v2.cmp(v1));
+    assert!(map
+        .iter()
+        .eq([(&'b', &3), (&'d', &3), (&'a', &2), (&'c', &1)]));
+    for key in ['a', 'b', 'c', 'd'] {
+        assert!(map.get(&key).is_some());
+    }
+    assert_consistent(&map);
+}
+
+#[test]
+fn sort_unstable_by_keeps_entries() {
+    let pairs = [(5, 'e'), (2, 'b'), (8, 'h'), (1, 'a'), (4, 'd')];
+    let mut map: IndexMap<i32, char> = pairs.into();
+    map.sort_unstable_by(|_k1, v1, _k2, v2| v2.cmp(v1));
+    assert_eq!(keys(&map), [8, 5, 4, 2, 1]);
+    for (key, value) in pairs {
+        assert_eq!(map.get(&key), Some(&value));
+    }
+    assert_eq!(map.len(), pairs.len());
+    assert_consistent(&map);
+}
+
+#[test]
+fn first_and_last() {
+    let mut map = IndexMap::new();
+    assert_eq!(map.first(), None);
+    assert_eq!(map.last_mut(), None);
+    map.insert('b', 2);
+    assert_eq!(map.first(), Some((&'b', &2)));
+    assert_eq!(map.last(), Some((&'b', &2)));
+    map.insert('a', 1);
+    map.insert('c', 3);
+    assert_eq!(map.first(), Some((&'b', &2)));
+    assert_eq!(map.last(), Some((&'c', &3)));
+    *map.first_mut().unwrap().1 = 20;
+    *map.last_mut().unwrap().1 = 30;
+    assert!(map.iter().eq([(&'b', &20), (&'a', &1), (&'c', &30)]));
+}
+
+#[test]
+fn pop_until_empty() {
+    let mut map: IndexMap<char, u32> = [('b', 2), ('a', 1), ('c', 3)].into();
+    assert_eq!(map.pop(), Some(('c', 3)));
+    assert_consistent(&map);
+    assert_eq!(map.pop(), Some(('a', 1)));
+    assert_eq!(map.pop(), Some(('b', 2)));
+    assert_eq!(map.pop(), None);
+    assert!(map.key2slot.is_empty());
+}
+
+#[test]
+fn truncate() {
+    let mut map: IndexMap<i32, i32> = [(5, 0), (2, 0), (8, 0), (1, 0)].into();
+    map.truncate(10);
+    assert_eq!(keys(&map), [5, 2, 8, 1]);
+    map.truncate(2);
+    assert_eq!(keys(&map), [5, 2]);
+    assert!(!map.contains_key(&8));
+    assert_consistent(&map);
+    map.truncate(0);
+    assert!(map.is_empty());
+    assert_consistent(&map);
+}
+
+#[test]
+fn split_off() {
+    let mut map: IndexMap<i32, char> = [(5, 'a'), (2, 'b'), (8, 'c'), (1, 'd')].into();
+    let tail = map.split_off(1);
+    assert!(map.iter().eq([(&5, &'a')]));
+    assert!(tail.iter().eq([(&2, &'b'), (&8, &'c'), (&1, &'d')]));
+    assert_eq!(tail.get(&1), Some(&'d'));
+    assert_eq!(tail.get_index_of(&8), Some(1));
+    assert_eq!(map.get(&2), None);
+    assert_consistent(&map);
+    assert_consistent(&tail);
+    assert!(map.split_off(1).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn split_off_out_of_bounds() {
+    let mut map: IndexMap<i32, char> = [(5, 'a')].into();
+    map.split_off(2);
+}
+
+#[test]
+fn get_or() {
+    let map: IndexMap<char, u32> = [('a', 1)].into();
+    let default = 0;
+    assert_eq!(*map.get_or(&'a', &default), 1);
+    assert!(core::ptr::eq(map.get_or(&'z', &default), &default));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn windows() {
+    let map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3)].into();
+    let windows: Vec<Vec<(&char, &u32)>> = map
+        .windows(2)
+        .map(|window| window.iter().collect())
+        .collect();
+    assert_eq!(
+        windows,
+        [[(&'a', &1), (&'b', &2)], [(&'b', &2), (&'c', &3)]]
+    );
+    assert_eq!(map.windows(2).len(), 2);
+    assert_eq!(
+        map.windows(2).next_back().unwrap().first(),
+        Some((&'b', &2))
+    );
+    assert_eq!(map.windows(4).count(), 0);
+}
+
+#[test]
+fn chunks() {
+    let map: IndexMap<char, u32> = [('a', 1), ('b', 2), ('c', 3)].into();
+    let chunks: Vec<EntrySlice<'_, char, u32>> = map.chunks(2).collect();
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks[0].iter().eq([(&'a', &1), (&'b', &2)]));
+    assert!(chunks[1].iter().eq([(&'c', &3)]));
+    assert_eq!(map.chunks(5).count(), 1);
+    assert_eq!(IndexMap::<char, u32>::new().chunks(2).count(), 0);
+}
+
+#[test]
+#[should_panic]
+fn windows_of_size_zero() {
+    let map: IndexMap<char, u32> = [('a', 1)].into();
+    map.windows(0);
+}
+
+#[test]
+#[should_panic]
+fn chunks_of_size_zero() {
+    let map: IndexMap<char, u32> = [('a', 1)].into();
+    map.chunks(0);
+}
+
+#[test]
+#[cfg(all(feature = "compact-index", target_pointer_width = "64"))]
+#[cfg_attr(
+    debug_assertions,
+    ignore = "filling the map with zero-sized slots is only fast with optimizations"
+)]
+fn try_insert_fails_beyond_compact_index_capacity() {
+    // Zero-sized slots allow to fill the map without allocating.
+    let len = u32::MAX as usize + 1;
+    let mut map = IndexMap {
+        key2slot: BTreeMap::new(),
+        slots: alloc::vec![Slot::new((), ()); len],
+        sorted_hint: false,
+    };
+    assert_eq!(map.try_insert((), ()), Err(CapacityError));
+    assert_eq!(map.len(), len);
+    assert!(map.key2slot.is_empty());
+}
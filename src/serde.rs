@@ -7,6 +7,9 @@ use serde::de::{
 };
 use serde::ser::{Serialize, Serializer};
 
+#[cfg(test)]
+mod tests;
+
 /// Requires crate feature `"serde"`
 impl<K, V> Serialize for IndexMap<K, V>
 where
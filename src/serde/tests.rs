@@ -0,0 +1,77 @@
+use super::*;
+use serde::de::value::Error as ValueError;
+use serde_test::{assert_de_tokens, assert_tokens, Token};
+
+#[test]
+fn map_round_trip_preserves_insertion_order() {
+    let map: IndexMap<i32, i32> = [(3, 30), (1, 10), (2, 20)].into();
+    assert_tokens(
+        &map,
+        &[
+            Token::Map { len: Some(3) },
+            Token::I32(3),
+            Token::I32(30),
+            Token::I32(1),
+            Token::I32(10),
+            Token::I32(2),
+            Token::I32(20),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn map_deserialize_duplicate_keys_last_write_wins() {
+    let map: IndexMap<i32, i32> = [(1, 11), (2, 20)].into();
+    assert_de_tokens(
+        &map,
+        &[
+            Token::Map { len: Some(3) },
+            Token::I32(1),
+            Token::I32(10),
+            Token::I32(2),
+            Token::I32(20),
+            Token::I32(1),
+            Token::I32(11),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn map_into_deserializer_round_trip() {
+    let map: IndexMap<i32, i32> = [(3, 30), (1, 10), (2, 20)].into();
+    let deserializer: MapDeserializer<'_, _, ValueError> = map.clone().into_deserializer();
+    assert_eq!(IndexMap::deserialize(deserializer).unwrap(), map);
+}
+
+#[test]
+fn set_round_trip_preserves_insertion_order() {
+    let set: IndexSet<i32> = [3, 1, 2].into();
+    assert_tokens(
+        &set,
+        &[
+            Token::Seq { len: Some(3) },
+            Token::I32(3),
+            Token::I32(1),
+            Token::I32(2),
+            Token::SeqEnd,
+        ],
+    );
+}
+
+#[test]
+fn set_deserialize_duplicate_values_keep_first_position() {
+    let set: IndexSet<i32> = [3, 1, 2].into();
+    assert_de_tokens(
+        &set,
+        &[
+            Token::Seq { len: Some(4) },
+            Token::I32(3),
+            Token::I32(1),
+            Token::I32(3),
+            Token::I32(2),
+            Token::SeqEnd,
+        ],
+    );
+}
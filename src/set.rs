@@ -1,14 +1,18 @@
 //! An ordered set based on a B-Tree that keeps insertion order of elements.
 
-use super::SlotIndex;
+use super::{CapacityError, SlotIndex};
 use alloc::collections::{btree_map, BTreeMap};
 use alloc::vec::IntoIter as VecIntoIter;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
-use core::iter::FusedIterator;
-use core::ops::Index;
+use core::iter::{Chain, FusedIterator};
+use core::mem::replace;
+use core::ops::{Index, Range};
 use core::slice::Iter as SliceIter;
 
+#[cfg(test)]
+mod tests;
+
 /// A b-tree set where the iteration order of the values
 /// is independent of the ordering of the values.
 ///
@@ -24,10 +28,10 @@ use core::slice::Iter as SliceIter;
 ///
 /// - Some operations such as `IndexSet::insert` now require `K: Clone`.
 /// - It is to be expected that this fork performs worse than the original
-/// [`indexmap` crate] implementation.
+///   [`indexmap` crate] implementation.
 /// - The implementation is based on `BTreeMap` internally instead of
-/// `HashMap` which has the effect that methods no longer require `K: Hash`
-/// but `K: Ord` instead.
+///   `HashMap` which has the effect that methods no longer require `K: Hash`
+///   but `K: Ord` instead.
 ///
 /// [`indexmap` crate]: https://crates.io/crates/indexmap
 /// [`wasmparser-nostd` crate]: https://crates.io/crates/wasmparser-nostd
@@ -66,6 +70,16 @@ impl<T> IndexSet<T> {
         }
     }
 
+    /// Returns the number of values the set can hold without reallocating.
+    ///
+    /// # Note
+    ///
+    /// This only refers to the storage of the values.
+    /// The b-tree based value index has no notion of capacity.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
     /// Reserve capacity for at least `additional` more values.
     pub fn reserve(&mut self, additional: usize) {
         self.slots.reserve(additional);
@@ -78,7 +92,7 @@ impl<T> IndexSet<T> {
 
     /// Returns `true` if the set contains no elements.
     pub fn is_empty(&self) -> bool {
-        self.len() != 0
+        self.len() == 0
     }
 
     /// Returns `true` if `self` has no elements in common with `other`.
@@ -87,8 +101,11 @@ impl<T> IndexSet<T> {
     where
         T: Ord,
     {
-        self.iter().all(|value| !other.contains(value))
-            && other.iter().all(|value| !self.contains(value))
+        if self.len() <= other.len() {
+            self.iter().all(|value| !other.contains(value))
+        } else {
+            other.iter().all(|value| !self.contains(value))
+        }
     }
 
     /// Returns `true` if the set is a subset of another,
@@ -97,6 +114,9 @@ impl<T> IndexSet<T> {
     where
         T: Ord,
     {
+        if self.len() > other.len() {
+            return false;
+        }
         self.iter().all(|value| other.contains(value))
     }
 
@@ -109,15 +129,85 @@ impl<T> IndexSet<T> {
         other.is_subset(self)
     }
 
+    /// Compares `self` against `other` and returns their differences.
+    ///
+    /// The returned [`SetDiff`] treats `self` as the old state and `other`
+    /// as the new state of the set.
+    pub fn diff<'a>(&'a self, other: &'a IndexSet<T>) -> SetDiff<'a, T>
+    where
+        T: Ord,
+    {
+        SetDiff {
+            old: self,
+            new: other,
+        }
+    }
+
+    /// Returns an iterator over all elements that are contained in `self` or `other`.
+    ///
+    /// Yields all elements of `self` in insertion order followed by all elements
+    /// of `other` that are not contained in `self` in the insertion order of `other`.
+    pub fn union<'a>(&'a self, other: &'a IndexSet<T>) -> Union<'a, T>
+    where
+        T: Ord,
+    {
+        Union {
+            iter: self.slots.iter(),
+            other: other.slots.iter(),
+            set: self,
+        }
+    }
+
+    /// Returns an iterator over all elements that are contained in both `self` and `other`.
+    ///
+    /// Yields the elements in the insertion order of `self`.
+    pub fn intersection<'a>(&'a self, other: &'a IndexSet<T>) -> Intersection<'a, T>
+    where
+        T: Ord,
+    {
+        Intersection {
+            iter: self.slots.iter(),
+            other,
+        }
+    }
+
+    /// Returns an iterator over all elements that are contained in `self` but not in `other`.
+    ///
+    /// Yields the elements in the insertion order of `self`.
+    pub fn difference<'a>(&'a self, other: &'a IndexSet<T>) -> Difference<'a, T>
+    where
+        T: Ord,
+    {
+        Difference {
+            iter: self.slots.iter(),
+            other,
+        }
+    }
+
+    /// Returns an iterator over all elements that are contained in either `self` or
+    /// `other` but not in both.
+    ///
+    /// Yields the elements of `self` that are not contained in `other` in the insertion
+    /// order of `self` followed by the elements of `other` that are not contained in
+    /// `self` in the insertion order of `other`.
+    pub fn symmetric_difference<'a>(&'a self, other: &'a IndexSet<T>) -> SymmetricDifference<'a, T>
+    where
+        T: Ord,
+    {
+        SymmetricDifference {
+            iter: self.difference(other).chain(other.difference(self)),
+        }
+    }
+
     /// Returns `true` if the set contains an element equal to the value.
     ///
     /// The value may be any borrowed form of the set's element type,
     /// but the ordering on the borrowed form *must* match the
     /// ordering on the element type.
-    pub fn contains<Q: ?Sized>(&self, key: &Q) -> bool
+    pub fn contains<Q>(&self, key: &Q) -> bool
     where
         T: Borrow<Q> + Ord,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
         self.key2slot.contains_key(key)
     }
@@ -128,10 +218,10 @@ impl<T> IndexSet<T> {
     /// The value may be any borrowed form of the set's element type,
     /// but the ordering on the borrowed form *must* match the
     /// ordering on the element type.
-    pub fn get<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
     where
         T: Borrow<Q> + Ord,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
         self.key2slot
             .get(value)
@@ -140,17 +230,19 @@ impl<T> IndexSet<T> {
 
     /// Returns the index-value pair corresponding to the supplied value.
     ///
-    /// The supplied key may be any borrowed form of the map's key type,
-    /// but the ordering on the borrowed form *must* match the ordering
-    /// on the key type.
-    pub fn get_full<Q: ?Sized>(&self, key: &Q) -> Option<(usize, &T)>
+    /// The returned value is the same stored element that is returned by
+    /// [`IndexSet::get`] and [`IndexSet::get_index`].
+    ///
+    /// The value may be any borrowed form of the set's element type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the element type.
+    pub fn get_full<Q>(&self, value: &Q) -> Option<(usize, &T)>
     where
         T: Borrow<Q> + Ord,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
-        self.key2slot
-            .get_key_value(key)
-            .map(|(key, slot)| (slot.index(), key))
+        let index = self.key2slot.get(value)?.index();
+        Some((index, &self.slots[index]))
     }
 
     /// Returns the unique index corresponding to the supplied value.
@@ -158,10 +250,10 @@ impl<T> IndexSet<T> {
     /// The supplied key may be any borrowed form of the map's key type,
     /// but the ordering on the borrowed form *must* match the ordering
     /// on the key type.
-    pub fn get_index_of<Q: ?Sized>(&self, key: &Q) -> Option<usize>
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
     where
         T: Borrow<Q> + Ord,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
         self.key2slot.get(key).copied().map(SlotIndex::index)
     }
@@ -171,6 +263,41 @@ impl<T> IndexSet<T> {
         self.slots.get(index)
     }
 
+    /// Returns the index of the first value in insertion order
+    /// for which `f` returns `true`.
+    ///
+    /// Returns `None` if `f` returns `false` for all values.
+    pub fn position<F>(&self, f: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.slots.iter().position(f)
+    }
+
+    /// Returns the index of the last value in insertion order
+    /// for which `f` returns `true`.
+    ///
+    /// Returns `None` if `f` returns `false` for all values.
+    pub fn rposition<F>(&self, f: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.slots.iter().rposition(f)
+    }
+
+    /// Gets an iterator over the indices of all values for which `f`
+    /// returns `true` in ascending order.
+    pub fn indices_where<'a, F>(&'a self, mut f: F) -> impl Iterator<Item = usize> + 'a
+    where
+        F: FnMut(&T) -> bool + 'a,
+    {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(move |(_index, value)| f(value))
+            .map(|(index, _value)| index)
+    }
+
     /// Adds a value to the set.
     ///
     /// Returns whether the value was newly inserted. That is:
@@ -187,6 +314,25 @@ impl<T> IndexSet<T> {
         inserted
     }
 
+    /// Adds a value to the set.
+    ///
+    /// Behaves like [`IndexSet::insert`] but returns an error instead of panicking
+    /// if the set cannot hold any more values.
+    ///
+    /// # Errors
+    ///
+    /// If the value is not present and the set already holds the maximum number
+    /// of values. This can only happen with the `compact-index` crate feature enabled.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, CapacityError>
+    where
+        T: Ord + Clone,
+    {
+        if !self.contains(&value) {
+            SlotIndex::try_new(self.len())?;
+        }
+        Ok(self.insert(value))
+    }
+
     /// Adds a value to the set.
     ///
     /// Returns the unique index to the value as well as a `bool` flag telling
@@ -203,32 +349,337 @@ impl<T> IndexSet<T> {
         match self.key2slot.entry(value.clone()) {
             btree_map::Entry::Vacant(entry) => {
                 let index = self.slots.len();
-                entry.insert(SlotIndex(index));
+                entry.insert(SlotIndex::new(index));
                 self.slots.push(value);
                 (index, true)
             }
-            btree_map::Entry::Occupied(entry) => {
-                let index = entry.get().index();
-                self.slots[index] = value;
-                (index, false)
+            btree_map::Entry::Occupied(entry) => (entry.get().index(), false),
+        }
+    }
+
+    /// Adds all values of `values` to the set in order.
+    ///
+    /// Reserves capacity for all values up front.
+    ///
+    /// Values that are already present, either in the set or earlier in
+    /// `values`, keep their position and are not added again.
+    pub fn extend_from_slice(&mut self, values: &[T])
+    where
+        T: Ord + Copy,
+    {
+        self.reserve(values.len());
+        self.extend(values)
+    }
+
+    /// Adds all values of `iter` to the set and returns the set for chaining.
+    ///
+    /// Reserves capacity for the lower bound of the size hint of `iter` up front.
+    ///
+    /// Values that are already present, either in the set or earlier in
+    /// `iter`, keep their position and are not added again.
+    pub fn collect_into<I>(&mut self, iter: I) -> &mut Self
+    where
+        T: Ord + Clone,
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        self.extend(iter);
+        self
+    }
+
+    /// Adds a value to the set, replacing the existing value, if any, that is
+    /// equal to the given one.
+    ///
+    /// Returns the replaced value.
+    pub fn replace(&mut self, value: T) -> Option<T>
+    where
+        T: Ord + Clone,
+    {
+        let (_index, replaced) = self.replace_full(value);
+        replaced
+    }
+
+    /// Adds a value to the set, replacing the existing value, if any, that is
+    /// equal to the given one.
+    ///
+    /// Returns the unique index to the value as well as the replaced value.
+    /// A replaced value keeps its position in the set.
+    pub fn replace_full(&mut self, value: T) -> (usize, Option<T>)
+    where
+        T: Ord + Clone,
+    {
+        match self.key2slot.remove_entry(&value) {
+            Some((_old_value, slot)) => {
+                let index = slot.index();
+                self.key2slot.insert(value.clone(), slot);
+                let replaced = replace(&mut self.slots[index], value);
+                (index, Some(replaced))
+            }
+            None => {
+                let index = self.slots.len();
+                self.key2slot.insert(value.clone(), SlotIndex::new(index));
+                self.slots.push(value);
+                (index, None)
+            }
+        }
+    }
+
+    /// Removes the value from the set and returns whether it was present.
+    ///
+    /// Like [`Vec::swap_remove`], the value is removed by swapping it with
+    /// the last value of the set and popping it off.
+    /// **This perturbs the position of what used to be the last value!**
+    pub fn swap_remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Removes the value from the set and returns whether it was present.
+    ///
+    /// Like [`Vec::remove`], the value is removed by shifting all of the
+    /// values that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those values!**
+    pub fn shift_remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        match self.key2slot.remove(value) {
+            Some(slot) => {
+                self.shift_remove_slot(slot.index());
+                true
             }
+            None => false,
         }
     }
 
+    /// Removes and returns the value in the set, if any, that is equal to the given one.
+    ///
+    /// Like [`Vec::swap_remove`], the value is removed by swapping it with
+    /// the last value of the set and popping it off.
+    /// **This perturbs the position of what used to be the last value!**
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        let index = self.key2slot.remove(value)?.index();
+        Some(self.swap_remove_slot(index))
+    }
+
+    /// Removes the last value in insertion order and returns it.
+    ///
+    /// Like [`Vec::pop`], this does not perturb the index of any other value.
+    ///
+    /// Returns `None` if the set is empty.
+    pub fn pop(&mut self) -> Option<T>
+    where
+        T: Ord,
+    {
+        let value = self.slots.pop()?;
+        self.key2slot.remove(&value);
+        Some(value)
+    }
+
+    /// Consumes the set and returns a new set with the elements transformed by `f`.
+    ///
+    /// Elements for which `f` returns `None` are dropped. If `f` maps multiple
+    /// elements to equal values only the first of them is kept.
+    /// Preserves the insertion order of the remaining elements.
+    pub fn filter_map<U, F>(self, f: F) -> IndexSet<U>
+    where
+        U: Ord + Clone,
+        F: FnMut(T) -> Option<U>,
+    {
+        self.slots.into_iter().filter_map(f).collect()
+    }
+
+    /// Retains only the elements for which `keep` returns `true`.
+    ///
+    /// Visits the elements in insertion order and preserves the
+    /// insertion order of the retained elements.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        T: Ord,
+        F: FnMut(&T) -> bool,
+    {
+        let key2slot = &mut self.key2slot;
+        let mut index = 0;
+        self.slots.retain(|value| {
+            if !keep(value) {
+                key2slot.remove(value);
+                return false;
+            }
+            let slot_index = key2slot
+                .get_mut(value)
+                .expect("IndexSet: missing slot index for value");
+            *slot_index = SlotIndex::new(index);
+            index += 1;
+            true
+        });
+    }
+
+    /// Retains only the elements that are also contained in `other`.
+    ///
+    /// Preserves the insertion order of the retained elements.
+    pub fn retain_in(&mut self, other: &Self)
+    where
+        T: Ord,
+    {
+        self.retain(|value| other.contains(value))
+    }
+
+    /// Removes all elements that are also contained in `other`.
+    ///
+    /// Preserves the insertion order of the remaining elements.
+    pub fn remove_all(&mut self, other: &Self)
+    where
+        T: Ord,
+    {
+        self.retain(|value| !other.contains(value))
+    }
+
+    /// Turns `self` into the union of `self` and `other`.
+    ///
+    /// Appends all elements of `other` that are not contained in `self`
+    /// in the insertion order of `other`. Elements that are already contained
+    /// in `self` keep their position and are not replaced.
+    pub fn union_with(&mut self, other: &Self)
+    where
+        T: Ord + Clone,
+    {
+        for value in other {
+            if !self.contains(value) {
+                self.insert(value.clone());
+            }
+        }
+    }
+
+    /// Turns `self` into the symmetric difference of `self` and `other`.
+    ///
+    /// Removes all elements that are also contained in `other` preserving the
+    /// insertion order of the remaining elements and then appends all elements
+    /// of `other` that were not contained in `self` in the insertion order of `other`.
+    pub fn symmetric_difference_with(&mut self, other: &Self)
+    where
+        T: Ord + Clone,
+    {
+        let added: Vec<T> = other
+            .iter()
+            .filter(|value| !self.contains(*value))
+            .cloned()
+            .collect();
+        self.remove_all(other);
+        self.extend(added);
+    }
+
     /// Gets an iterator that visits the elements in the [`IndexSet`]
     /// in the order in which they have been inserted into the set unless
     /// there have been removals.
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             iter: self.slots.iter(),
         }
     }
 
+    /// Returns all elements of the set alongside their indices in insertion order.
+    pub fn enumerate_vec(&self) -> Vec<(usize, &T)> {
+        self.iter().enumerate().collect()
+    }
+
+    /// Consumes the set and returns an iterator over its elements in ascending order.
+    ///
+    /// This is independent of the insertion order of the elements.
+    pub fn into_iter_sorted(self) -> impl Iterator<Item = T>
+    where
+        T: Ord,
+    {
+        let mut slots: Vec<Option<T>> = self.slots.into_iter().map(Some).collect();
+        self.key2slot.into_values().map(move |slot| {
+            slots[slot.index()]
+                .take()
+                .expect("IndexSet: slot has already been taken")
+        })
+    }
+
+    /// Gets an iterator over the elements of the set in insertion order that
+    /// stops at the first element for which `f` returns `false`.
+    pub fn iter_while<F>(&self, mut f: F) -> impl Iterator<Item = &T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().take_while(move |value| f(value))
+    }
+
     /// Clears the set, removing all elements.
     pub fn clear(&mut self) {
         self.key2slot.clear();
         self.slots.clear();
     }
+
+    /// Shortens the set, keeping the first `len` values in insertion order
+    /// and dropping the rest.
+    ///
+    /// Like [`Vec::truncate`], this has no effect if `len` is greater than or
+    /// equal to the current length of the set.
+    pub fn truncate(&mut self, len: usize)
+    where
+        T: Ord,
+    {
+        if len >= self.len() {
+            return;
+        }
+        for value in &self.slots[len..] {
+            self.key2slot.remove(value);
+        }
+        self.slots.truncate(len);
+    }
+
+    /// Removes the slot at `index` by swapping it with the last slot.
+    ///
+    /// The value of the removed slot must have been removed from `key2slot` already.
+    fn swap_remove_slot(&mut self, index: usize) -> T
+    where
+        T: Ord,
+    {
+        let removed = self.slots.swap_remove(index);
+        if index < self.slots.len() {
+            self.reindex(index..index + 1);
+        }
+        removed
+    }
+
+    /// Removes the slot at `index` by shifting all following slots.
+    ///
+    /// The value of the removed slot must have been removed from `key2slot` already.
+    fn shift_remove_slot(&mut self, index: usize) -> T
+    where
+        T: Ord,
+    {
+        let removed = self.slots.remove(index);
+        self.reindex(index..self.len());
+        removed
+    }
+
+    /// Updates the slot indices of all slots within `range`.
+    ///
+    /// This needs to be called after slots have been moved within `slots`.
+    fn reindex(&mut self, range: Range<usize>)
+    where
+        T: Ord,
+    {
+        for index in range {
+            let slot_index = self
+                .key2slot
+                .get_mut(&self.slots[index])
+                .expect("IndexSet: missing slot index for value");
+            *slot_index = SlotIndex::new(index);
+        }
+    }
 }
 
 impl<T> Index<usize> for IndexSet<T> {
@@ -391,3 +842,211 @@ impl<T> ExactSizeIterator for IntoIter<T> {
 }
 
 impl<T> FusedIterator for IntoIter<T> {}
+
+/// The differences between two [`IndexSet`]s.
+///
+/// This `struct` is created by the [`diff`] method on [`IndexSet`]. See its
+/// documentation for more.
+///
+/// [`diff`]: IndexSet::diff
+#[derive(Debug)]
+pub struct SetDiff<'a, T> {
+    /// The set that is treated as the old state.
+    old: &'a IndexSet<T>,
+    /// The set that is treated as the new state.
+    new: &'a IndexSet<T>,
+}
+
+impl<'a, T> SetDiff<'a, T>
+where
+    T: Ord,
+{
+    /// Gets an iterator over the elements that are only present in the old set.
+    ///
+    /// The elements are yielded in the insertion order of the old set.
+    pub fn removed(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let new = self.new;
+        self.old.iter().filter(move |value| !new.contains(*value))
+    }
+
+    /// Gets an iterator over the elements that are only present in the new set.
+    ///
+    /// The elements are yielded in the insertion order of the new set.
+    pub fn added(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let old = self.old;
+        self.new.iter().filter(move |value| !old.contains(*value))
+    }
+
+    /// Returns `true` if both sets contain the same elements.
+    ///
+    /// The insertion order of the elements is not compared.
+    pub fn is_empty(&self) -> bool {
+        self.removed().next().is_none() && self.added().next().is_none()
+    }
+}
+
+/// A lazy iterator producing elements in the union of two [`IndexSet`]s.
+///
+/// This `struct` is created by the [`union`] method on [`IndexSet`]. See its
+/// documentation for more.
+///
+/// [`union`]: IndexSet::union
+#[derive(Debug)]
+pub struct Union<'a, T> {
+    /// The remaining elements of the first set.
+    iter: SliceIter<'a, T>,
+    /// The remaining elements of the second set.
+    other: SliceIter<'a, T>,
+    /// The first set.
+    set: &'a IndexSet<T>,
+}
+
+impl<'a, T> Clone for Union<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            other: self.other.clone(),
+            set: self.set,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Union<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.iter.len();
+        (len, Some(len + self.other.len()))
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.iter.next() {
+            return Some(value);
+        }
+        let set = self.set;
+        self.other.find(|value| !set.contains(*value))
+    }
+}
+
+impl<'a, T> FusedIterator for Union<'a, T> where T: Ord {}
+
+/// A lazy iterator producing elements in the intersection of two [`IndexSet`]s.
+///
+/// This `struct` is created by the [`intersection`] method on [`IndexSet`].
+/// See its documentation for more.
+///
+/// [`intersection`]: IndexSet::intersection
+#[derive(Debug)]
+pub struct Intersection<'a, T> {
+    /// The remaining elements of the first set.
+    iter: SliceIter<'a, T>,
+    /// The second set.
+    other: &'a IndexSet<T>,
+}
+
+impl<'a, T> Clone for Intersection<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            other: self.other,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Intersection<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.iter.len()))
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|value| other.contains(*value))
+    }
+}
+
+impl<'a, T> FusedIterator for Intersection<'a, T> where T: Ord {}
+
+/// A lazy iterator producing elements in the difference of two [`IndexSet`]s.
+///
+/// This `struct` is created by the [`difference`] method on [`IndexSet`].
+/// See its documentation for more.
+///
+/// [`difference`]: IndexSet::difference
+#[derive(Debug)]
+pub struct Difference<'a, T> {
+    /// The remaining elements of the first set.
+    iter: SliceIter<'a, T>,
+    /// The second set.
+    other: &'a IndexSet<T>,
+}
+
+impl<'a, T> Clone for Difference<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            other: self.other,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Difference<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.iter.len()))
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|value| !other.contains(*value))
+    }
+}
+
+impl<'a, T> FusedIterator for Difference<'a, T> where T: Ord {}
+
+/// A lazy iterator producing elements in the symmetric difference of two [`IndexSet`]s.
+///
+/// This `struct` is created by the [`symmetric_difference`] method on [`IndexSet`].
+/// See its documentation for more.
+///
+/// [`symmetric_difference`]: IndexSet::symmetric_difference
+#[derive(Debug)]
+pub struct SymmetricDifference<'a, T> {
+    iter: Chain<Difference<'a, T>, Difference<'a, T>>,
+}
+
+impl<'a, T> Clone for SymmetricDifference<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for SymmetricDifference<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<'a, T> FusedIterator for SymmetricDifference<'a, T> where T: Ord {}
@@ -0,0 +1,445 @@
+use super::*;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// Asserts that the key index of `set` agrees with the positions of its values.
+fn assert_consistent<T>(set: &IndexSet<T>)
+where
+    T: Ord + fmt::Debug,
+{
+    assert_eq!(set.key2slot.len(), set.slots.len());
+    for (index, value) in set.slots.iter().enumerate() {
+        let slot_index = set.key2slot.get(value).map(|slot| slot.index());
+        assert_eq!(slot_index, Some(index), "wrong slot index for {value:?}");
+    }
+}
+
+/// A value whose ordering only considers its number and ignores its tag.
+#[derive(Debug, Clone)]
+struct Tagged(i32, &'static str);
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[test]
+fn replace_full_new_value() {
+    let mut set = IndexSet::new();
+    assert_eq!(set.replace_full(Tagged(1, "a")), (0, None));
+    assert_eq!(set.replace_full(Tagged(2, "a")), (1, None));
+    assert_eq!(set.len(), 2);
+    assert_consistent(&set);
+}
+
+#[test]
+fn replace_full_existing_value() {
+    let mut set: IndexSet<Tagged> = [Tagged(1, "a"), Tagged(2, "a")].into();
+    let (index, replaced) = set.replace_full(Tagged(1, "b"));
+    assert_eq!(index, 0);
+    assert_eq!(replaced.unwrap().1, "a");
+    assert_eq!(set.get_index(0).unwrap().1, "b");
+    assert_eq!(
+        set.key2slot.get_key_value(&Tagged(1, "z")).unwrap().0 .1,
+        "b"
+    );
+    assert_eq!(set.replace(Tagged(2, "c")).unwrap().1, "a");
+    assert_eq!(set.get(&Tagged(2, "z")).unwrap().1, "c");
+    assert_eq!(set.len(), 2);
+    assert_consistent(&set);
+}
+
+#[test]
+fn iter_while_stops_at_first_failing_value() {
+    let set: IndexSet<i32> = [1, 2, 0, 3].into();
+    let mut calls = 0;
+    let taken: Vec<_> = set
+        .iter_while(|value| {
+            calls += 1;
+            *value > 0
+        })
+        .collect();
+    assert_eq!(taken, [&1, &2]);
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn position_and_rposition() {
+    let set: IndexSet<i32> = [1, 2, 4, 3].into();
+    assert_eq!(set.position(|value| value % 2 == 0), Some(1));
+    assert_eq!(set.rposition(|value| value % 2 == 0), Some(2));
+    assert_eq!(set.position(|value| *value > 9), None);
+    assert_eq!(set.rposition(|value| *value > 9), None);
+}
+
+#[test]
+fn extend_from_slice_reserves_up_front() {
+    let values: Vec<u32> = (0..100).collect();
+    let mut set = IndexSet::new();
+    set.extend_from_slice(&values);
+    assert_eq!(set.len(), 100);
+    assert!(set.capacity() >= values.len());
+    assert_consistent(&set);
+}
+
+#[test]
+fn extend_from_slice_inserts_duplicates_once() {
+    let mut set: IndexSet<u32> = [2].into();
+    set.extend_from_slice(&[3, 1, 3, 2, 1]);
+    assert!(set.iter().eq(&[2, 3, 1]));
+    assert_consistent(&set);
+}
+
+#[test]
+fn indices_where() {
+    let set: IndexSet<i32> = [5, 2, 8, 1, 4].into();
+    let indices: Vec<usize> = set.indices_where(|value| value % 2 == 0).collect();
+    assert_eq!(indices, [1, 2, 4]);
+    let expected: Vec<usize> = set
+        .iter()
+        .enumerate()
+        .filter(|(_index, value)| *value % 2 == 0)
+        .map(|(index, _value)| index)
+        .collect();
+    assert_eq!(indices, expected);
+    assert_eq!(set.indices_where(|value| *value > 9).count(), 0);
+}
+
+#[test]
+fn get_full() {
+    let set: IndexSet<Tagged> = [Tagged(3, "a"), Tagged(1, "a"), Tagged(2, "a")].into();
+    for probe in [Tagged(3, "z"), Tagged(1, "z"), Tagged(2, "z")] {
+        let (index, stored) = set.get_full(&probe).unwrap();
+        assert_eq!(Some(index), set.get_index_of(&probe));
+        assert!(core::ptr::eq(stored, set.get_index(index).unwrap()));
+        assert_eq!(stored.1, "a");
+    }
+    assert_eq!(set.get_full(&Tagged(4, "a")), None);
+}
+
+#[test]
+fn insert_full_keeps_stored_value() {
+    let mut set: IndexSet<Tagged> = [Tagged(1, "a"), Tagged(2, "a")].into();
+    assert_eq!(set.insert_full(Tagged(2, "b")), (1, false));
+    let (index, stored) = set.get_full(&Tagged(2, "z")).unwrap();
+    assert_eq!((index, stored.1), (1, "a"));
+    assert_eq!(set.insert_full(Tagged(3, "b")), (2, true));
+    assert_consistent(&set);
+}
+
+#[test]
+fn retain_in_matches_intersection() {
+    let mut set: IndexSet<i32> = [5, 1, 4, 2, 3].into();
+    let other: IndexSet<i32> = [2, 9, 5, 3].into();
+    let expected: Vec<i32> = set.intersection(&other).copied().collect();
+    set.retain_in(&other);
+    assert!(set.iter().eq(&expected));
+    assert_eq!(expected, [5, 2, 3]);
+    assert_consistent(&set);
+}
+
+#[test]
+fn remove_all_matches_difference() {
+    let mut set: IndexSet<i32> = [5, 1, 4, 2, 3].into();
+    let other: IndexSet<i32> = [2, 9, 5, 3].into();
+    let expected: Vec<i32> = set.difference(&other).copied().collect();
+    set.remove_all(&other);
+    assert!(set.iter().eq(&expected));
+    assert_eq!(expected, [1, 4]);
+    assert_consistent(&set);
+}
+
+#[test]
+fn diff_reports_removed_and_added() {
+    let old: IndexSet<i32> = [1, 2, 3, 4].into();
+    let new: IndexSet<i32> = [6, 3, 5, 1].into();
+    let diff = old.diff(&new);
+    assert!(diff.removed().eq(&[2, 4]));
+    assert!(diff.added().eq(&[6, 5]));
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn diff_of_identical_sets_is_empty() {
+    let old: IndexSet<i32> = [1, 2, 3].into();
+    let new: IndexSet<i32> = [3, 1, 2].into();
+    let diff = old.diff(&new);
+    assert_eq!(diff.removed().count(), 0);
+    assert_eq!(diff.added().count(), 0);
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn into_iter_sorted_yields_ascending_values() {
+    let set: IndexSet<i32> = [3, 1, 4, 2].into();
+    let values: Vec<i32> = set.into_iter_sorted().collect();
+    assert_eq!(values, [1, 2, 3, 4]);
+    assert_eq!(IndexSet::<i32>::new().into_iter_sorted().count(), 0);
+}
+
+#[test]
+fn enumerate_vec() {
+    let set: IndexSet<char> = ['c', 'a', 'b'].into();
+    let entries = set.enumerate_vec();
+    assert_eq!(entries, [(0, &'c'), (1, &'a'), (2, &'b')]);
+    assert!(entries.iter().map(|(_index, value)| *value).eq(set.iter()));
+}
+
+#[test]
+fn filter_map_dedups_mapped_values() {
+    let set: IndexSet<i32> = [5, 12, 7, 15, 3, 21].into();
+    let set = set.filter_map(|value| (value != 7).then_some(value % 10));
+    assert!(set.iter().eq(&[5, 2, 3, 1]));
+    assert_eq!(set.get_index_of(&1), Some(3));
+    assert_consistent(&set);
+}
+
+#[test]
+fn is_empty() {
+    let mut set = IndexSet::new();
+    assert!(set.is_empty());
+    set.insert('a');
+    assert!(!set.is_empty());
+    set.clear();
+    assert!(set.is_empty());
+}
+
+#[test]
+fn symmetric_difference_with_matches_lazy_iterator() {
+    let mut set: IndexSet<i32> = [5, 1, 4, 2].into();
+    let other: IndexSet<i32> = [7, 2, 6, 5].into();
+    let expected: Vec<i32> = set.symmetric_difference(&other).copied().collect();
+    set.symmetric_difference_with(&other);
+    assert!(set.iter().eq(&expected));
+    assert!(set.iter().eq(&[1, 4, 7, 6]));
+    assert_consistent(&set);
+}
+
+#[test]
+fn union_with_appends_new_values() {
+    let mut set: IndexSet<Tagged> = [Tagged(3, "a"), Tagged(1, "a")].into();
+    let other: IndexSet<Tagged> = [Tagged(4, "b"), Tagged(1, "b"), Tagged(2, "b")].into();
+    set.union_with(&other);
+    let values: Vec<(i32, &str)> = set.iter().map(|value| (value.0, value.1)).collect();
+    assert_eq!(values, [(3, "a"), (1, "a"), (4, "b"), (2, "b")]);
+    assert_consistent(&set);
+}
+
+#[test]
+fn collect_into_chains() {
+    let mut set = IndexSet::new();
+    set.collect_into(0..4).collect_into([2, 8, 9]);
+    assert!(set.iter().eq(&[0, 1, 2, 3, 8, 9]));
+    let capacity = set.capacity();
+    set.collect_into(core::iter::empty());
+    assert_eq!(set.capacity(), capacity);
+    assert_consistent(&set);
+}
+
+#[test]
+fn with_capacity_and_reserve() {
+    let mut set: IndexSet<i32> = IndexSet::with_capacity(10);
+    assert!(set.capacity() >= 10);
+    assert!(set.is_empty());
+    let capacity = set.capacity();
+    set.extend(0..10);
+    assert_eq!(set.capacity(), capacity);
+    set.reserve(20);
+    assert!(set.capacity() >= 30);
+    assert_eq!(set.len(), 10);
+}
+
+#[test]
+fn try_insert() {
+    let mut set = IndexSet::new();
+    assert_eq!(set.try_insert('a'), Ok(true));
+    assert_eq!(set.try_insert('b'), Ok(true));
+    assert_eq!(set.try_insert('a'), Ok(false));
+    assert!(set.iter().eq(&['a', 'b']));
+    assert_consistent(&set);
+}
+
+#[test]
+#[cfg(all(feature = "compact-index", target_pointer_width = "64"))]
+fn try_insert_fails_beyond_compact_index_capacity() {
+    // Zero-sized values allow to fill the set without allocating.
+    let len = u32::MAX as usize + 1;
+    let mut set = IndexSet {
+        key2slot: BTreeMap::new(),
+        slots: alloc::vec![(); len],
+    };
+    assert_eq!(set.try_insert(()), Err(CapacityError));
+    assert_eq!(set.len(), len);
+    assert!(set.key2slot.is_empty());
+}
+
+#[test]
+fn retain_keeps_even_values() {
+    let mut set = IndexSet::new();
+    for value in [1, 2, 3, 4, 5, 6] {
+        set.insert(value);
+    }
+    set.retain(|value| value % 2 == 0);
+    assert!(set.iter().eq(&[2, 4, 6]));
+    assert_eq!(set.get_index_of(&6), Some(2));
+    assert!(!set.contains(&1));
+    assert_consistent(&set);
+}
+
+#[test]
+fn swap_remove() {
+    let mut set: IndexSet<char> = ['a', 'b', 'c', 'd'].into();
+    assert!(set.swap_remove(&'b'));
+    assert!(!set.swap_remove(&'b'));
+    assert!(set.iter().eq(&['a', 'd', 'c']));
+    assert_eq!(set.get_index_of(&'d'), Some(1));
+    assert!(set.swap_remove(&'c'));
+    assert!(set.iter().eq(&['a', 'd']));
+    assert_consistent(&set);
+}
+
+#[test]
+fn shift_remove() {
+    let mut set: IndexSet<char> = ['a', 'b', 'c', 'd'].into();
+    assert!(set.shift_remove(&'b'));
+    assert!(!set.shift_remove(&'b'));
+    assert!(set.iter().eq(&['a', 'c', 'd']));
+    assert_eq!(set.get_index_of(&'c'), Some(1));
+    assert_eq!(set.get_index_of(&'d'), Some(2));
+    assert_consistent(&set);
+}
+
+#[test]
+fn take_returns_stored_value() {
+    let mut set: IndexSet<Tagged> = [Tagged(1, "a"), Tagged(2, "a"), Tagged(3, "a")].into();
+    let taken = set.take(&Tagged(1, "z")).unwrap();
+    assert_eq!((taken.0, taken.1), (1, "a"));
+    assert_eq!(set.take(&Tagged(1, "z")), None);
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.get_index_of(&Tagged(3, "z")), Some(0));
+    assert_consistent(&set);
+}
+
+#[test]
+fn union_yields_self_then_new_values() {
+    let lhs: IndexSet<i32> = [3, 1, 2].into();
+    let rhs: IndexSet<i32> = [4, 2, 5, 3].into();
+    let union = lhs.union(&rhs);
+    assert!(union.clone().eq(&[3, 1, 2, 4, 5]));
+    let mut union = union;
+    assert_eq!(union.by_ref().count(), 5);
+    assert_eq!(union.next(), None);
+    assert!(lhs.union(&IndexSet::new()).eq(&[3, 1, 2]));
+}
+
+#[test]
+fn intersection() {
+    let set: IndexSet<i32> = [3, 1, 4, 2].into();
+    let disjoint: IndexSet<i32> = [5, 6].into();
+    let overlapping: IndexSet<i32> = [2, 9, 3].into();
+    assert_eq!(set.intersection(&disjoint).count(), 0);
+    assert!(set.intersection(&set).eq(&[3, 1, 4, 2]));
+    assert!(set.intersection(&overlapping).eq(&[3, 2]));
+    assert!(overlapping.intersection(&set).eq(&[2, 3]));
+}
+
+#[test]
+fn difference() {
+    let set: IndexSet<i32> = [3, 1, 4, 2].into();
+    let superset: IndexSet<i32> = [1, 2, 3, 4, 5].into();
+    let overlapping: IndexSet<i32> = [2, 9, 3].into();
+    assert!(set.difference(&IndexSet::new()).eq(&[3, 1, 4, 2]));
+    assert_eq!(set.difference(&superset).count(), 0);
+    assert!(set.difference(&overlapping).eq(&[1, 4]));
+}
+
+#[test]
+fn symmetric_difference() {
+    let lhs: IndexSet<i32> = [3, 1, 4, 2].into();
+    let rhs: IndexSet<i32> = [6, 2, 5, 3].into();
+    let iter = lhs.symmetric_difference(&rhs);
+    assert!(iter.clone().eq(&[1, 4, 6, 5]));
+    assert!(rhs.symmetric_difference(&lhs).eq(&[6, 5, 1, 4]));
+    assert_eq!(lhs.symmetric_difference(&lhs).count(), 0);
+}
+
+#[test]
+fn symmetric_difference_clones_without_clone_values() {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct NoClone(i32);
+
+    // Sets of non-`Clone` values cannot be built through the public API.
+    let set = |values: [i32; 2]| IndexSet {
+        key2slot: values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (NoClone(*value), SlotIndex::new(index)))
+            .collect(),
+        slots: values.iter().map(|value| NoClone(*value)).collect(),
+    };
+    let lhs = set([1, 2]);
+    let rhs = set([2, 3]);
+    let mut iter = lhs.symmetric_difference(&rhs);
+    assert_eq!(iter.next(), Some(&NoClone(1)));
+    assert!(iter.clone().eq([&NoClone(3)]));
+    assert!(iter.eq([&NoClone(3)]));
+}
+
+#[test]
+fn subset_superset_and_disjoint() {
+    let empty = IndexSet::<i32>::new();
+    let small: IndexSet<i32> = [2, 1].into();
+    let large: IndexSet<i32> = [1, 2, 3].into();
+    let other: IndexSet<i32> = [4, 5].into();
+    assert!(empty.is_subset(&small));
+    assert!(small.is_superset(&empty));
+    assert!(empty.is_disjoint(&empty));
+    assert!(small.is_subset(&large));
+    assert!(!large.is_subset(&small));
+    assert!(large.is_superset(&small));
+    assert!(!small.is_superset(&large));
+    assert!(large.is_subset(&large));
+    assert!(large.is_superset(&large));
+    assert!(large.is_disjoint(&other));
+    assert!(!large.is_disjoint(&small));
+}
+
+#[test]
+fn pop_until_empty() {
+    let mut set: IndexSet<char> = ['b', 'a', 'c'].into();
+    assert_eq!(set.pop(), Some('c'));
+    assert_consistent(&set);
+    assert_eq!(set.pop(), Some('a'));
+    assert_eq!(set.pop(), Some('b'));
+    assert_eq!(set.pop(), None);
+    assert!(set.key2slot.is_empty());
+}
+
+#[test]
+fn truncate() {
+    let mut set: IndexSet<i32> = [5, 2, 8, 1].into();
+    set.truncate(10);
+    assert!(set.iter().eq(&[5, 2, 8, 1]));
+    set.truncate(2);
+    assert!(set.iter().eq(&[5, 2]));
+    assert!(!set.contains(&8));
+    assert_consistent(&set);
+    set.truncate(0);
+    assert!(set.is_empty());
+    assert_consistent(&set);
+}
@@ -0,0 +1,25 @@
+use super::*;
+
+#[test]
+fn slot_index_round_trips() {
+    for index in [0, 1, 42, u16::MAX as usize] {
+        assert_eq!(SlotIndex::new(index).index(), index);
+        assert_eq!(SlotIndex::try_new(index).map(SlotIndex::index), Ok(index));
+    }
+}
+
+#[test]
+#[cfg(all(feature = "compact-index", target_pointer_width = "64"))]
+fn slot_index_rejects_indices_beyond_u32() {
+    let max = u32::MAX as usize;
+    assert_eq!(SlotIndex::try_new(max).map(SlotIndex::index), Ok(max));
+    assert_eq!(SlotIndex::try_new(max + 1), Err(CapacityError));
+    assert_eq!(SlotIndex::try_new(usize::MAX), Err(CapacityError));
+}
+
+#[test]
+#[should_panic]
+#[cfg(all(feature = "compact-index", target_pointer_width = "64"))]
+fn slot_index_new_panics_beyond_u32() {
+    SlotIndex::new(u32::MAX as usize + 1);
+}